@@ -1,11 +1,16 @@
-#![allow(clippy::single_match)]
-use keyseq::winit::pkey;
+use keyseq::{
+    _keyseq,
+    repeat::{RepeatMatcher, RepeatPolicy},
+    winit::pchord,
+};
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use std::time::{Duration, Instant};
 #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
 use winit::{
     dpi::LogicalSize,
-    event::{ElementState, Event, WindowEvent},
-    event_loop::EventLoop,
-    keyboard::{ModifiersState, PhysicalKey},
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    keyboard::ModifiersState,
     // WARNING: This is not available on all platforms (for example on the web).
     // platform::modifier_supplement::KeyEventExtModifierSupplement,
     window::Window,
@@ -19,7 +24,7 @@ fn main() {
 #[rustfmt::skip]
 #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
 fn main() -> Result<(), impl std::error::Error> {
-    println!("Press A key with different modifier keys.");
+    println!("Press A key with different modifier keys, or hold an arrow key.");
     let event_loop = EventLoop::new().unwrap();
 
     let attributes = Window::default_attributes()
@@ -27,36 +32,54 @@ fn main() -> Result<(), impl std::error::Error> {
     let _window = event_loop.create_window(attributes).unwrap();
 
     let mut modifiers = ModifiersState::default();
+    let start = Instant::now();
+    let ms = Duration::from_millis;
+    // The matcher drops OS repeats of `Ignore` bindings, so there is no need
+    // to check `event.repeat` by hand.
+    let bindings = [
+        (pchord!{ Ctrl-A },           RepeatPolicy::Ignore, "Just pressed Ctrl-A!"),
+        (pchord!{ Super-A },          RepeatPolicy::Ignore, "Just pressed Super-A!"),
+        (pchord!{ Ctrl-Alt-A },       RepeatPolicy::Ignore, "Just pressed Ctrl-Alt-A!"),
+        (pchord!{ Ctrl-Shift-A },     RepeatPolicy::Ignore, "Just pressed Ctrl-Shift-A!"),
+        (pchord!{ Alt-Shift-A },      RepeatPolicy::Ignore, "Just pressed Alt-Shift-A!"),
+        (pchord!{ Ctrl-Alt-Shift-A }, RepeatPolicy::Ignore, "Just pressed Ctrl-Alt-Shift-A!"),
+        (pchord!{ Shift-A },          RepeatPolicy::Ignore, "Just pressed Shift-A!"),
+        (pchord!{ Alt-A },            RepeatPolicy::Ignore, "Just pressed Alt-A!"),
+        (pchord!{ A },                RepeatPolicy::Ignore, "Just pressed A!"),
+        (pchord!{ ArrowRight },       RepeatPolicy::Os,     "Right, at the OS's rate"),
+        (pchord!{ ArrowLeft },        RepeatPolicy::Custom { delay: ms(300), interval: ms(100) },
+                                                            "Left, every 100ms after 300ms"),
+    ];
+    let mut matcher = RepeatMatcher::default();
+    for (chord, policy, _) in &bindings {
+        matcher.add(*chord, *policy);
+    }
 
     event_loop.run(move |event, elwt| {
-        if let Event::WindowEvent { event, .. } = event {
-            match event {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
                 WindowEvent::CloseRequested => elwt.exit(),
                 WindowEvent::ModifiersChanged(new) => {
                     modifiers = new.state();
                 }
                 WindowEvent::KeyboardInput { event, .. } => {
-                    if event.state == ElementState::Pressed && !event.repeat {
-                        if let PhysicalKey::Code(key_code) = event.physical_key {
-                            match (modifiers.into(), key_code) {
-                                pkey!{ Ctrl-A } | pkey!{ Super-A } => println!("Just pressed Ctrl-A or Super-A!"),
-                                pkey!{ Ctrl-Alt-A }                => println!("Just pressed Ctrl-Alt-A!"),
-                                pkey!{ Ctrl-Shift-A }              => println!("Just pressed Ctrl-Shift-A!"),
-                                pkey!{ Alt-Shift-A }               => println!("Just pressed Alt-Shift-A!"),
-                                pkey!{ Ctrl-Alt-Shift-A }          => println!("Just pressed Alt-Shift-A!"),
-                                pkey!{ Shift-A }                   => println!("Just pressed Shift-A!"),
-                                pkey!{ Alt-A }                     => println!("Just pressed Alt-A!"),
-                                pkey!{ Super-A }                   => println!("Just pressed Super-A!"),
-                                pkey!{ A }                         => println!("Just pressed A!"),
-                                _                                  => println!("No key matched."),
-                            }
-                        }
-                    }
-                }
-                WindowEvent::RedrawRequested => {
+                    matcher.key_event(modifiers.into(), &event, start.elapsed());
                 }
                 _ => (),
+            },
+            Event::AboutToWait => {
+                // A custom repeat needs updates while its key is held.
+                matcher.update(start.elapsed());
+                elwt.set_control_flow(if matcher.is_pending() {
+                    ControlFlow::WaitUntil(Instant::now() + ms(10))
+                } else {
+                    ControlFlow::Wait
+                });
             }
-        };
+            _ => (),
+        }
+        for index in matcher.drain() {
+            println!("{}", bindings[index].2);
+        }
     })
 }
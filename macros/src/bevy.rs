@@ -18,9 +18,16 @@ pub fn get_key(tree: TokenTree) -> Option<TokenStream> {
 }
 
 pub fn get_pkey(tree: TokenTree) -> Option<TokenStream> {
+    if matches!(tree, TokenTree::Ident(ref ident) if ident == "Leader") {
+        // The placeholder of keyseq's `Leader for KeyCode`, resolved by a
+        // keymap at runtime.
+        return Some(quote! {
+            ::bevy::prelude::KeyCode::Unidentified(::bevy::input::keyboard::NativeKeyCode::Xkb(0))
+        });
+    }
     match tree {
         TokenTree::Literal(ref literal) => {
-            let x = literal.span().source_text().unwrap();
+            let x = literal.to_string();
             if x.len() == 1 && x.parse::<u8>().is_ok() {
                 Some(Ident::new(&format!("Digit{x}"), Span::call_site()))
                 // Some(Ident::new("Keyx", Span::call_site()))
@@ -75,7 +82,7 @@ pub fn get_pkey(tree: TokenTree) -> Option<TokenStream> {
             name.map(|n| Ident::new(n, punct.span()))
         }
         TokenTree::Ident(ref ident) => {
-            let label = ident.span().source_text().unwrap();
+            let label = ident.to_string();
             if label.len() == 1 {
                 let name: Option<Cow<'static, str>> = match label.chars().next().unwrap() {
                     x @ 'A'..='Z' => Some(format!("Key{x}").into()),
@@ -0,0 +1,589 @@
+use super::{modifier_from_name, read_modifier_bits, to_keyseq_modifiers};
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use proc_macro_error::{abort, abort_call_site, abort_if_dirty, emit_error};
+use quote::quote;
+
+/// A chord with a concrete key, which may be stored in a table.
+pub struct KeyChord {
+    pub mods: u8,
+    pub key: TokenStream,
+    pub text: String,
+}
+
+/// A key sequence bound to an action.
+pub struct Entry {
+    pub chords: Vec<KeyChord>,
+    pub action: Ident,
+    /// The doc comment written before the binding, if any.
+    pub description: String,
+    pub span: Span,
+}
+
+impl Entry {
+    pub fn text(&self) -> String {
+        self.chords
+            .iter()
+            .map(|chord| chord.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The key type and key reader of a backend.
+pub struct Backend {
+    pub key_type: TokenStream,
+    pub get_key: fn(TokenTree) -> Option<TokenStream>,
+}
+
+impl Backend {
+    pub fn from_ident(ident: &Ident) -> Backend {
+        match ident.to_string().as_str() {
+            #[cfg(feature = "poor")]
+            "poor" => Backend {
+                key_type: quote! { &'static str },
+                get_key: super::get_pkey,
+            },
+            #[cfg(feature = "bevy")]
+            "bevy" => Backend {
+                key_type: quote! { ::bevy::prelude::KeyCode },
+                get_key: super::bevy::get_pkey,
+            },
+            #[cfg(feature = "winit")]
+            "winit" => Backend {
+                key_type: quote! { ::winit::keyboard::KeyCode },
+                get_key: super::winit::get_pkey,
+            },
+            _ => abort!(ident, "Expected an enabled backend: poor, bevy, or winit"),
+        }
+    }
+}
+
+/// Return the text of a key as it is written, e.g., `;` or `'['` is `[`.
+fn key_text(tree: &TokenTree) -> String {
+    let text = tree.to_string();
+    match text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        Some(inner) => inner.replace("\\\\", "\\").replace("\\'", "'"),
+        None => text,
+    }
+}
+
+/// Return the text of a `#[doc = "..."]` attribute's brackets.
+fn doc_text(group: &proc_macro2::Group) -> Option<String> {
+    let mut i = group.stream().into_iter();
+    match (i.next(), i.next(), i.next()) {
+        (
+            Some(TokenTree::Ident(ident)),
+            Some(TokenTree::Punct(punct)),
+            Some(TokenTree::Literal(literal)),
+        ) if ident == "doc" && punct.as_char() == '=' => {
+            let text = literal.to_string();
+            // Doc comments arrive as raw strings, e.g., `r" Save the file"`.
+            let (raw, quoted) = match text.strip_prefix('r') {
+                Some(raw) => (true, raw.trim_matches('#')),
+                None => (false, text.as_str()),
+            };
+            let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+            Some(if raw {
+                inner.to_string()
+            } else {
+                inner.replace("\\\"", "\"").replace("\\\\", "\\")
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Join the lines of doc comments into one description.
+fn join_docs(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Read a key sequence like `Ctrl-X Ctrl-S` whose keys are concrete, so no
+/// ranges, optional modifiers, or modifier-only chords.
+pub fn read_key_seq(
+    mut input: TokenStream,
+    get_key: fn(TokenTree) -> Option<TokenStream>,
+) -> Vec<KeyChord> {
+    let mut chords = vec![];
+    while !input.is_empty() {
+        let span = input.clone().into_iter().next().unwrap().span();
+        let (mods, optional, rest) = read_modifier_bits(input);
+        if optional != 0 {
+            abort!(
+                span,
+                "Optional modifiers are not supported in a key binding"
+            );
+        }
+        let mut i = rest.into_iter();
+        let Some(tree) = i.next() else {
+            abort!(span, "Expected a key");
+        };
+        match tree {
+            TokenTree::Ident(ref ident) if modifier_from_name(&ident.to_string()).is_some() => {
+                abort!(tree, "A key binding needs a key, not only modifiers");
+            }
+            TokenTree::Group(_) => abort!(tree, "Key ranges are not supported in a key binding"),
+            _ => {}
+        }
+        let mut text = String::new();
+        for (bit, name) in [(1, "Ctrl"), (2, "Alt"), (4, "Shift"), (8, "Super")] {
+            if mods & bit != 0 {
+                text.push_str(name);
+                text.push('-');
+            }
+        }
+        text.push_str(&key_text(&tree));
+        let key_span = tree.span();
+        let key = get_key(tree).unwrap_or_else(|| abort!(key_span, "Expected a key"));
+        chords.push(KeyChord { mods, key, text });
+        input = TokenStream::from_iter(i);
+    }
+    chords
+}
+
+/// Read `Ctrl-X Ctrl-S => Save, Ctrl-Z => Undo`, each binding optionally after
+/// a doc comment describing its action.
+fn read_entries(input: TokenStream, backend: &Backend) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut i = input.into_iter().peekable();
+    while i.peek().is_some() {
+        let mut docs = vec![];
+        while matches!(i.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+            let hash = i.next().unwrap();
+            match i.next() {
+                Some(TokenTree::Group(ref group)) => match doc_text(group) {
+                    Some(text) => docs.push(text),
+                    None => abort!(group, "Only doc comments may precede a binding"),
+                },
+                _ => abort!(hash, "Expected a doc comment"),
+            }
+        }
+        let mut keys = vec![];
+        loop {
+            match i.next() {
+                Some(TokenTree::Punct(ref punct))
+                    if punct.as_char() == '='
+                        && punct.spacing() == Spacing::Joint
+                        && matches!(i.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '>') =>
+                {
+                    i.next();
+                    break;
+                }
+                Some(tree) => keys.push(tree),
+                None => abort_call_site!("Expected `=> Action` after the keys"),
+            }
+        }
+        if keys.is_empty() {
+            abort_call_site!("Expected keys before `=>`");
+        }
+        let action = match i.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            Some(tree) => abort!(tree, "Expected an action name"),
+            None => abort_call_site!("Expected an action name"),
+        };
+        match i.next() {
+            None => {}
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            Some(tree) => abort!(tree, "Expected a comma"),
+        }
+        let span = keys[0].span();
+        let chords = read_key_seq(TokenStream::from_iter(keys), backend.get_key);
+        entries.push(Entry {
+            chords,
+            action,
+            description: join_docs(&docs),
+            span,
+        });
+    }
+    entries
+}
+
+/// How held modifiers are matched, which matches `keyseq::conflict::MatchMode`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MatchMode {
+    Exact,
+    Superset,
+}
+
+impl MatchMode {
+    fn from_ident(ident: &Ident) -> MatchMode {
+        match ident.to_string().as_str() {
+            "exact" => MatchMode::Exact,
+            "superset" => MatchMode::Superset,
+            _ => abort!(ident, "Expected a match mode: exact or superset"),
+        }
+    }
+
+    fn matches(self, bound: u8, held: u8) -> bool {
+        match self {
+            MatchMode::Exact => bound == held,
+            MatchMode::Superset => held & bound == bound,
+        }
+    }
+}
+
+/// Report duplicate, prefix, and superset conflicts as errors at both
+/// bindings. This mirrors `keyseq::conflict::conflict_kind()`: with superset
+/// matching, the binding whose modifiers include the other's wins, so only
+/// bindings that neither includes conflict.
+pub fn check_conflicts(entries: &[Entry], mode: MatchMode) {
+    let key = |chord: &KeyChord| (chord.mods, chord.key.to_string());
+    for (j, b) in entries.iter().enumerate() {
+        for a in &entries[..j] {
+            let n = a.chords.len().min(b.chords.len());
+            let same = a.chords[..n]
+                .iter()
+                .zip(&b.chords[..n])
+                .all(|(x, y)| key(x) == key(y));
+            let message = if same && a.chords.len() == b.chords.len() {
+                format!(
+                    "`{}` is bound to both {} and {}",
+                    b.text(),
+                    a.action,
+                    b.action
+                )
+            } else if same {
+                let (short, long) = if a.chords.len() < b.chords.len() {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                format!("`{}` shadows `{}`", short.text(), long.text())
+            } else if mode == MatchMode::Superset
+                && a.chords[..n]
+                    .iter()
+                    .zip(&b.chords[..n])
+                    .all(|(x, y)| x.key.to_string() == y.key.to_string())
+            {
+                let includes = |x: &Entry, y: &Entry| {
+                    x.chords
+                        .iter()
+                        .zip(&y.chords)
+                        .all(|(c, d)| mode.matches(d.mods, c.mods))
+                };
+                if a.chords.len() != b.chords.len() {
+                    let (short, long) = if a.chords.len() < b.chords.len() {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    };
+                    format!(
+                        "`{}` shadows `{}` with superset matching",
+                        short.text(),
+                        long.text()
+                    )
+                } else if !includes(a, b) && !includes(b, a) {
+                    format!(
+                        "`{}` and `{}` both match when their modifiers are held together",
+                        a.text(),
+                        b.text()
+                    )
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+            emit_error!(b.span, "{}", message);
+            emit_error!(a.span, "{}; the other binding is here", message);
+        }
+    }
+    abort_if_dirty();
+}
+
+/// Return the distinct actions in the order they first appear with the first
+/// description of their bindings.
+pub fn actions(entries: &[Entry]) -> Vec<(&Ident, String)> {
+    let mut actions: Vec<(&Ident, String)> = vec![];
+    for entry in entries {
+        match actions.iter_mut().find(|(a, _)| **a == entry.action) {
+            Some((_, description)) if description.is_empty() => {
+                description.clone_from(&entry.description);
+            }
+            Some(_) => {}
+            None => actions.push((&entry.action, entry.description.clone())),
+        }
+    }
+    actions
+}
+
+/// Generate the `KeyBindings` and `Display` implementations for `name`.
+pub fn impl_key_bindings(
+    name: &Ident,
+    key_type: &TokenStream,
+    mode: MatchMode,
+    variants: &[(&Ident, String)],
+    entries: &[Entry],
+) -> TokenStream {
+    let bindings = entries.iter().map(|entry| {
+        let keys = entry.chords.iter().map(|chord| {
+            let mods = to_keyseq_modifiers(chord.mods);
+            let key = &chord.key;
+            quote! { (#mods, #key) }
+        });
+        let text = Literal::string(&entry.text());
+        let action = &entry.action;
+        quote! {
+            _keyseq::bindings::Binding { keys: &[#(#keys),*], text: #text, action: #name::#action }
+        }
+    });
+    let descriptions = variants.iter().map(|(_, d)| Literal::string(d));
+    let names = variants
+        .iter()
+        .map(|(v, _)| Literal::string(&v.to_string()));
+    let variants: Vec<&Ident> = variants.iter().map(|(v, _)| *v).collect();
+    let match_mode = match mode {
+        MatchMode::Exact => quote! {},
+        MatchMode::Superset => quote! {
+            const MATCH_MODE: _keyseq::conflict::MatchMode = _keyseq::conflict::MatchMode::Superset;
+        },
+    };
+    quote! {
+        impl _keyseq::bindings::KeyBindings for #name {
+            type Key = #key_type;
+            #match_mode
+
+            fn bindings() -> &'static [_keyseq::bindings::Binding<#key_type, Self>] {
+                const BINDINGS: &[_keyseq::bindings::Binding<#key_type, #name>] = &[#(#bindings),*];
+                BINDINGS
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#name::#variants => #names),*
+                }
+            }
+
+            fn description(&self) -> &'static str {
+                match self {
+                    #(#name::#variants => #descriptions),*
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                _keyseq::bindings::fmt_action(self, f)
+            }
+        }
+    }
+}
+
+/// Read `[#[attr]] [vis] Name for backend[, mode] { keys => Action, ... }`.
+pub fn keymap(input: TokenStream) -> TokenStream {
+    let mut i = input.into_iter().peekable();
+    let mut attrs = vec![];
+    while let Some(TokenTree::Punct(punct)) = i.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        attrs.push(i.next().unwrap());
+        match i.next() {
+            Some(group @ TokenTree::Group(_)) => attrs.push(group),
+            _ => abort_call_site!("Expected an attribute"),
+        }
+    }
+    let mut vis = vec![];
+    if matches!(i.peek(), Some(TokenTree::Ident(ident)) if ident == "pub") {
+        vis.push(i.next().unwrap());
+        if matches!(i.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+        {
+            vis.push(i.next().unwrap());
+        }
+    }
+    let name = match i.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        Some(tree) => abort!(tree, "Expected the name of the action enum"),
+        None => abort_call_site!("Expected the name of the action enum"),
+    };
+    match i.next() {
+        Some(TokenTree::Ident(ref ident)) if ident == "for" => {}
+        Some(tree) => abort!(tree, "Expected `for` and a backend: poor, bevy, or winit"),
+        None => abort_call_site!("Expected `for` and a backend: poor, bevy, or winit"),
+    }
+    let backend = match i.next() {
+        Some(TokenTree::Ident(ident)) => Backend::from_ident(&ident),
+        Some(tree) => abort!(tree, "Expected a backend: poor, bevy, or winit"),
+        None => abort_call_site!("Expected a backend: poor, bevy, or winit"),
+    };
+    let mut mode = MatchMode::Exact;
+    if matches!(i.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ',') {
+        i.next();
+        mode = match i.next() {
+            Some(TokenTree::Ident(ident)) => MatchMode::from_ident(&ident),
+            Some(tree) => abort!(tree, "Expected a match mode: exact or superset"),
+            None => abort_call_site!("Expected a match mode: exact or superset"),
+        };
+    }
+    let body = match i.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(tree) => abort!(tree, "Expected the bindings in braces"),
+        None => abort_call_site!("Expected the bindings in braces"),
+    };
+    if let Some(tree) = i.next() {
+        abort!(tree, "Unexpected tokens after the bindings");
+    }
+    let entries = read_entries(body, &backend);
+    check_conflicts(&entries, mode);
+    let variants = actions(&entries);
+    let attrs = TokenStream::from_iter(attrs);
+    let vis = TokenStream::from_iter(vis);
+    let key_bindings = impl_key_bindings(&name, &backend.key_type, mode, &variants, &entries);
+    let variants = variants.iter().map(|(variant, description)| {
+        if description.is_empty() {
+            quote! { #variant }
+        } else {
+            quote! { #[doc = #description] #variant }
+        }
+    });
+    quote! {
+        #attrs
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #name {
+            #(#variants),*
+        }
+
+        #key_bindings
+    }
+}
+
+/// Split tokens at top-level commas.
+fn split_commas(input: TokenStream) -> Vec<TokenStream> {
+    let mut parts = vec![TokenStream::new()];
+    for tree in input {
+        match tree {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => parts.push(TokenStream::new()),
+            tree => parts.last_mut().unwrap().extend([tree]),
+        }
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Return the contents of `#[name(...)]` if `group` is that attribute.
+fn attribute_args(group: &proc_macro2::Group, name: &str) -> Option<TokenStream> {
+    let mut i = group.stream().into_iter();
+    match (i.next(), i.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(args)))
+            if ident == name && args.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(args.stream())
+        }
+        _ => None,
+    }
+}
+
+/// Derive `KeyBindings` for an enum whose variants have `#[keys(...)]`
+/// attributes.
+pub fn derive_key_bindings(input: TokenStream) -> TokenStream {
+    let mut i = input.into_iter().peekable();
+    let mut backend = None;
+    let mut mode = MatchMode::Exact;
+    let mut name = None;
+    while let Some(tree) = i.next() {
+        match tree {
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Bracket => {
+                if let Some(args) = attribute_args(group, "key_bindings") {
+                    let mut args = split_commas(args).into_iter();
+                    let Some(TokenTree::Ident(ident)) =
+                        args.next().and_then(|arg| arg.into_iter().next())
+                    else {
+                        abort!(group, "Expected a backend: poor, bevy, or winit");
+                    };
+                    backend = Some(Backend::from_ident(&ident));
+                    if let Some(arg) = args.next() {
+                        match arg.into_iter().next() {
+                            Some(TokenTree::Ident(ident)) => mode = MatchMode::from_ident(&ident),
+                            _ => abort!(group, "Expected a match mode: exact or superset"),
+                        }
+                    }
+                }
+            }
+            TokenTree::Ident(ref ident) if ident == "enum" => {
+                name = match i.next() {
+                    Some(TokenTree::Ident(ident)) => Some(ident),
+                    _ => abort!(ident, "Expected the name of the enum"),
+                };
+                break;
+            }
+            TokenTree::Ident(ref ident) if ident == "struct" || ident == "union" => {
+                abort!(ident, "KeyBindings can only be derived for an enum");
+            }
+            _ => {}
+        }
+    }
+    let name =
+        name.unwrap_or_else(|| abort_call_site!("KeyBindings can only be derived for an enum"));
+    let body = match i.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(tree) => abort!(tree, "Generic enums are not supported"),
+        None => abort_call_site!("Expected the variants of the enum"),
+    };
+    let backend = backend
+        .unwrap_or_else(|| abort_call_site!("Expected a backend, e.g., `#[key_bindings(bevy)]`"));
+
+    let mut variants = vec![];
+    let mut entries = vec![];
+    let mut i = body.into_iter().peekable();
+    while i.peek().is_some() {
+        let mut keys = vec![];
+        let mut docs = vec![];
+        while matches!(i.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+            i.next();
+            if let Some(TokenTree::Group(ref group)) = i.next() {
+                if let Some(args) = attribute_args(group, "keys") {
+                    keys.extend(split_commas(args));
+                } else if let Some(text) = doc_text(group) {
+                    docs.push(text);
+                }
+            }
+        }
+        let variant = match i.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            Some(tree) => abort!(tree, "Expected a variant"),
+            None => abort_call_site!("Expected a variant"),
+        };
+        // Skip any discriminant up to the next comma.
+        for tree in i.by_ref() {
+            match tree {
+                TokenTree::Punct(ref punct) if punct.as_char() == ',' => break,
+                TokenTree::Group(_) => abort!(tree, "Only unit variants are supported"),
+                _ => {}
+            }
+        }
+        for seq in keys {
+            let span = seq.clone().into_iter().next().unwrap().span();
+            entries.push(Entry {
+                chords: read_key_seq(seq, backend.get_key),
+                action: variant.clone(),
+                description: join_docs(&docs),
+                span,
+            });
+        }
+        variants.push((variant, join_docs(&docs)));
+    }
+    check_conflicts(&entries, mode);
+    let variants: Vec<(&Ident, String)> = variants.iter().map(|(v, d)| (v, d.clone())).collect();
+    let key_type = &backend.key_type;
+    let key_bindings = impl_key_bindings(&name, key_type, mode, &variants, &entries);
+    quote! {
+        #key_bindings
+
+        impl #name {
+            /// Return the bindings declared with `#[keys(...)]`.
+            pub fn default_bindings() -> &'static [_keyseq::bindings::Binding<#key_type, #name>] {
+                <#name as _keyseq::bindings::KeyBindings>::bindings()
+            }
+
+            /// Return the key sequences bound to this action by default.
+            pub fn default_keys(self) -> impl Iterator<Item = &'static [(_keyseq::Modifiers, #key_type)]> {
+                <#name as _keyseq::bindings::KeyBindings>::bindings_of(self).map(|binding| binding.keys)
+            }
+        }
+    }
+}
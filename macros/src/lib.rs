@@ -2,7 +2,7 @@
 #![doc = include_str!("../README.md")]
 extern crate proc_macro;
 #[allow(unused_imports)]
-use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
 #[allow(unused_imports)]
 use proc_macro_error::{abort, abort_call_site, emit_call_site_warning, proc_macro_error};
 use quote::quote;
@@ -15,6 +15,12 @@ mod winit;
 #[cfg(feature = "bevy")]
 mod bevy;
 
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+mod keymap;
+
+mod key_names;
+use key_names::NAMED_KEYS;
+
 /// Short hand notation describes a physical key chord as `(modifiers: u8,
 /// key_code: &str)`.
 ///
@@ -32,6 +38,14 @@ mod bevy;
 /// assert_eq!(pkey! { Alt-1 }, (2, "Key1"));
 /// ```
 ///
+/// A chord of only modifiers has no key, so it is an error. Use `pchord!`,
+/// which expands to a `Chord` whose key is `None`.
+///
+/// ```compile_fail
+/// # use keyseq_macros::poor_pkey as pkey;
+/// let _ = pkey! { Ctrl-Shift };
+/// ```
+///
 /// More than one key will cause a panic at compile-time. Use keyseq! for that.
 ///
 /// ```compile_fail
@@ -56,7 +70,7 @@ mod bevy;
 #[proc_macro_error]
 #[proc_macro]
 pub fn poor_pkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let (result, leftover) = read_key_chord(input.into(), to_modifiers_u8, get_pkey);
+    let (result, leftover) = read_pkey_chord(input.into(), to_modifiers_u8, get_pkey);
     if !leftover.is_empty() {
         abort!(leftover, "Too many tokens; use keyseq! for multiple keys");
     }
@@ -70,7 +84,7 @@ pub fn poor_pkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 #[proc_macro_error]
 #[proc_macro]
 pub fn bevy_pkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let (result, leftover) = read_key_chord(input.into(), to_keyseq_modifiers, bevy::get_pkey);
+    let (result, leftover) = read_pkey_chord(input.into(), to_keyseq_modifiers, bevy::get_pkey);
     if !leftover.is_empty() {
         abort!(leftover, "Too many tokens; use keyseq! for multiple keys");
     }
@@ -104,7 +118,7 @@ pub fn bevy_lkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 #[proc_macro]
 pub fn winit_pkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // let (result, leftover) = read_key_chord(input.into(), winit::to_modifiers, winit::get_pkey);
-    let (result, leftover) = read_key_chord(input.into(), to_keyseq_modifiers, winit::get_pkey);
+    let (result, leftover) = read_pkey_chord(input.into(), to_keyseq_modifiers, winit::get_pkey);
     if !leftover.is_empty() {
         abort!(leftover, "Too many tokens; use keyseq! for multiple keys");
     }
@@ -137,6 +151,18 @@ pub fn winit_pkey(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///     let _ = key!(A B);
 /// }
 /// ```
+///
+/// A named key must be a W3C key value like `Enter` or `ArrowUp`.
+///
+/// ```
+/// # use keyseq_macros::poor_lkey as key;
+/// assert_eq!(key! { Ctrl-Space }, (1, "Space"));
+/// ```
+///
+/// ```compile_fail
+/// # use keyseq_macros::poor_lkey as key;
+/// let _ = key! { Ctrl-Sapce };
+/// ```
 #[cfg(feature = "poor")]
 #[proc_macro_error]
 #[proc_macro]
@@ -214,12 +240,19 @@ pub fn poor_lkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(keyseq_macros::poor_pkeyseq! { Ctrl-W Alt-D Shift-S Super-A },
 ///            [(1, "W"), (2, "D"), (4, "S"), (8, "A")]);
 /// ```
+///
+/// Every element has the same type, so a chord of only modifiers is an
+/// error. Use `pchordseq!` for sequences with one.
+///
+/// ```compile_fail
+/// let _ = keyseq_macros::poor_pkeyseq! { Ctrl-Shift A };
+/// ```
 /// [keycode]: https://docs.rs/bevy/latest/bevy/prelude/enum.KeyCode.html
 #[cfg(feature = "poor")]
 #[proc_macro_error]
 #[proc_macro]
 pub fn poor_pkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let keys = read_key_chords(input.into(), to_modifiers_u8, get_pkey);
+    let keys = read_pkey_chords(input.into(), to_modifiers_u8, get_pkey);
     quote! {
         [#(#keys),*]
     }
@@ -233,7 +266,7 @@ pub fn poor_pkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 #[proc_macro_error]
 #[proc_macro]
 pub fn bevy_pkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let keys = read_key_chords(input.into(), to_keyseq_modifiers, bevy::get_pkey);
+    let keys = read_pkey_chords(input.into(), to_keyseq_modifiers, bevy::get_pkey);
     quote! {
         [#(#keys),*]
     }
@@ -267,7 +300,7 @@ pub fn bevy_lkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 #[proc_macro]
 pub fn winit_pkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // let keys = read_key_chords(input.into(), winit::to_modifiers, winit::get_pkey);
-    let keys = read_key_chords(input.into(), to_keyseq_modifiers, winit::get_pkey);
+    let keys = read_pkey_chords(input.into(), to_keyseq_modifiers, winit::get_pkey);
     quote! {
         [#(#keys),*]
     }
@@ -294,6 +327,350 @@ pub fn winit_lkeyseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     .into()
 }
 
+/// Short hand notation describes a physical key chord as a
+/// `keyseq::Chord<&str>`, which may have optional modifiers.
+///
+/// ```
+/// # use keyseq_macros::poor_pchord as pchord;
+/// # mod _keyseq {
+/// #     #[derive(Debug, PartialEq)]
+/// #     pub struct Modifiers(pub u8);
+/// #     #[derive(Debug, PartialEq)]
+/// #     pub enum Press { Press, Tap, DoubleTap, Hold(std::time::Duration), Release }
+/// #     impl Press { pub const DEFAULT_HOLD: std::time::Duration = std::time::Duration::from_millis(500); }
+/// #     #[derive(Debug, PartialEq)]
+/// #     pub struct Chord<K> { pub mods: Modifiers, pub optional: Modifiers, pub key: Option<K>, pub press: Press }
+/// # }
+/// # use _keyseq::*;
+/// assert_eq!(pchord! { Ctrl-Shift?-Equal },
+///            Chord { mods: Modifiers(1), optional: Modifiers(4), key: Some("Equal"), press: Press::Press });
+/// assert_eq!(pchord! { Ctrl-Shift },
+///            Chord { mods: Modifiers(5), optional: Modifiers(0), key: None, press: Press::Tap });
+/// assert_eq!(pchord! { Shift Shift },
+///            Chord { mods: Modifiers(4), optional: Modifiers(0), key: None, press: Press::DoubleTap });
+/// assert_eq!(pchord! { Hold(Space, 750) },
+///            Chord { mods: Modifiers(0), optional: Modifiers(0), key: Some("Space"),
+///                    press: Press::Hold(std::time::Duration::from_millis(750)) });
+/// ```
+///
+/// Only a chord may be repeated.
+///
+/// ```compile_fail
+/// # use keyseq_macros::poor_pchord as pchord;
+/// let _ = pchord! { Shift A };
+/// ```
+#[cfg(feature = "poor")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn poor_pchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(input.into(), get_pkey, quote! { &'static str }, "pchordseq").into()
+}
+
+/// Short hand notation describes a logical key chord as a
+/// `keyseq::Chord<&str>`, which may have optional modifiers.
+///
+/// ```ignore
+/// keyseq_macros::poor_lchord! { Ctrl-Shift?-= }
+/// ```
+#[cfg(feature = "poor")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn poor_lchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(input.into(), get_key, quote! { &'static str }, "lchordseq").into()
+}
+
+/// Short hand notation describes a sequence of physical key chords as
+/// `[keyseq::Chord<&str>]`.
+///
+/// ```ignore
+/// keyseq_macros::poor_pchordseq! { Ctrl-X Ctrl-Shift?-S }
+/// ```
+#[cfg(feature = "poor")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn poor_pchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(input.into(), get_pkey, quote! { &'static str });
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// Short hand notation describes a sequence of logical key chords as
+/// `[keyseq::Chord<&str>]`.
+///
+/// ```ignore
+/// keyseq_macros::poor_lchordseq! { Ctrl-x Ctrl-Shift?-s }
+/// ```
+#[cfg(feature = "poor")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn poor_lchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(input.into(), get_key, quote! { &'static str });
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::bevy_pchord! { Ctrl-Shift?-Equal }
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn bevy_pchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(
+        input.into(),
+        bevy::get_pkey,
+        quote! { ::bevy::prelude::KeyCode },
+        "pchordseq",
+    )
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::bevy_lchord! { Ctrl-Shift?-= }
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn bevy_lchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(
+        input.into(),
+        bevy::get_key,
+        quote! { ::bevy::input::keyboard::Key },
+        "lchordseq",
+    )
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::bevy_pchordseq! { Ctrl-X Ctrl-Shift?-S }
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn bevy_pchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(
+        input.into(),
+        bevy::get_pkey,
+        quote! { ::bevy::prelude::KeyCode },
+    );
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::bevy_lchordseq! { Ctrl-x Ctrl-Shift?-s }
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn bevy_lchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(
+        input.into(),
+        bevy::get_key,
+        quote! { ::bevy::input::keyboard::Key },
+    );
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// Short hand notation describes a physical key chord as
+/// `keyseq::Chord<`[winit::keyboard::KeyCode][keycode]`>`.
+///
+/// ```ignore
+/// keyseq_macros::winit_pchord! { Ctrl-Shift?-Equal }
+/// ```
+/// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
+#[cfg(feature = "winit")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn winit_pchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(
+        input.into(),
+        winit::get_pkey,
+        quote! { ::winit::keyboard::KeyCode },
+        "pchordseq",
+    )
+    .into()
+}
+
+/// Short hand notation describes a logical key chord as
+/// `keyseq::Chord<`[winit::keyboard::Key][key]`>`.
+///
+/// ```ignore
+/// keyseq_macros::winit_lchord! { Ctrl-Shift?-= }
+/// ```
+/// [key]: https://docs.rs/winit/latest/winit/keyboard/enum.Key.html
+#[cfg(feature = "winit")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn winit_lchord(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_one_chord(
+        input.into(),
+        winit::get_key,
+        quote! { ::winit::keyboard::Key<char> },
+        "lchordseq",
+    )
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::winit_pchordseq! { Ctrl-X Ctrl-Shift?-S }
+/// ```
+#[cfg(feature = "winit")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn winit_pchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(
+        input.into(),
+        winit::get_pkey,
+        quote! { ::winit::keyboard::KeyCode },
+    );
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// ```ignore
+/// keyseq_macros::winit_lchordseq! { Ctrl-x Ctrl-Shift?-s }
+/// ```
+#[cfg(feature = "winit")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn winit_lchordseq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let chords = read_chords(
+        input.into(),
+        winit::get_key,
+        quote! { ::winit::keyboard::Key<char> },
+    );
+    quote! {
+        [#(#chords),*]
+    }
+    .into()
+}
+
+/// Short hand notation describes a physical key combo, several keys pressed
+/// at once, as `(modifiers: u8, key_codes: [&str; N])`.
+///
+/// ```
+/// # use keyseq_macros::poor_pcombo as pcombo;
+/// assert_eq!(pcombo! { J+K }, (0, ["J", "K"]));
+/// assert_eq!(pcombo! { Ctrl-J+K+L }, (1, ["J", "K", "L"]));
+/// ```
+#[cfg(feature = "poor")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn poor_pcombo(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_combo(input.into(), to_modifiers_u8, get_pkey).into()
+}
+
+/// ```ignore
+/// keyseq_macros::bevy_pcombo! { J+K }
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn bevy_pcombo(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_combo(input.into(), to_keyseq_modifiers, bevy::get_pkey).into()
+}
+
+/// Short hand notation describes a physical key combo as `(modifiers:`
+/// [keyseq::Modifiers]`, key_codes: [`[winit::keyboard::KeyCode][keycode]`; N])`.
+///
+/// ```ignore
+/// keyseq_macros::winit_pcombo! { J+K }
+/// ```
+/// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
+#[cfg(feature = "winit")]
+#[proc_macro_error]
+#[proc_macro]
+pub fn winit_pcombo(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    read_combo(input.into(), to_keyseq_modifiers, winit::get_pkey).into()
+}
+
+/// Declare an action enum with its key bindings.
+///
+/// ```ignore
+/// keymap! {
+///     pub EditorKeys for bevy {
+///         Ctrl-X Ctrl-S => Save,
+///         Ctrl-Z => Undo,
+///     }
+/// }
+/// ```
+///
+/// This generates the enum `EditorKeys { Save, Undo }`, implements
+/// `keyseq::bindings::KeyBindings` for it with a static table of the bindings,
+/// and implements `Display` to show each action with its shortcuts. The backend
+/// is `poor`, `bevy`, or `winit` and determines the key type. Keys are physical
+/// keys as in `pkeyseq!`. A doc comment before a binding describes its action
+/// and documents the generated variant.
+///
+/// Duplicate bindings and bindings that shadow a longer sequence are compile
+/// errors. After the backend, `superset` selects superset matching, where the
+/// binding with the most modifiers wins, e.g., `Ctrl-Shift-A` over `Ctrl-A`. It
+/// also rejects bindings that neither wins, like `Ctrl-A` and `Alt-A` with
+/// Ctrl and Alt held.
+///
+/// ```ignore
+/// keymap! {
+///     pub Keys for bevy, superset {
+///         Ctrl-A => SelectAll,
+///         Ctrl-Shift-A => SelectNone,
+///         Alt-A => Archive, // error: `Ctrl-A` and `Alt-A` both match
+///     }
+/// }
+/// ```
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+#[proc_macro_error]
+#[proc_macro]
+pub fn keymap(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    keymap::keymap(input.into()).into()
+}
+
+/// Expand to the array of named logical keys the macros accept, e.g.,
+/// `"Enter"`, which keyseq's runtime notation shares.
+#[doc(hidden)]
+#[proc_macro]
+pub fn named_keys(_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let names = NAMED_KEYS;
+    quote! { [#(#names),*] }.into()
+}
+
+/// Derive `keyseq::bindings::KeyBindings` for an action enum.
+///
+/// ```ignore
+/// #[derive(Clone, Copy, PartialEq, KeyBindings)]
+/// #[key_bindings(bevy)]
+/// enum Action {
+///     #[keys(Ctrl-S)]
+///     Save,
+///     #[keys(Ctrl-X Ctrl-C, Alt-F4)]
+///     Quit,
+/// }
+/// ```
+///
+/// The `key_bindings` attribute names the backend and optionally the match
+/// mode, e.g., `#[key_bindings(bevy, superset)]`. Each `keys` attribute holds
+/// comma-separated key sequences in `pkeyseq!` notation. A variant's doc
+/// comment is the action's description. It also generates
+/// `default_bindings()`, `default_keys()`, and a `Display` that shows the
+/// shortcuts next to the action name.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+#[proc_macro_error]
+#[proc_macro_derive(KeyBindings, attributes(key_bindings, keys))]
+pub fn derive_key_bindings(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    keymap::derive_key_bindings(input.into()).into()
+}
+
 #[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
 fn read_key_chords<F, G>(mut input: TokenStream, to_modifiers: F, get_key: G) -> Vec<TokenStream>
 where
@@ -324,7 +701,7 @@ fn key_code_path(id: Ident) -> TokenStream {
 fn get_pkey(tree: TokenTree) -> Option<TokenStream> {
     match tree {
         TokenTree::Literal(ref literal) => {
-            let x = literal.span().source_text().unwrap();
+            let x = literal.to_string();
             if x.len() == 1 && x.parse::<u8>().is_ok() {
                 Some(Ident::new(&format!("Key{x}"), Span::call_site()))
                 // Some(Ident::new("Keyx", Span::call_site()))
@@ -365,7 +742,7 @@ fn get_pkey(tree: TokenTree) -> Option<TokenStream> {
             name.map(|n| Ident::new(n, punct.span()))
         }
         TokenTree::Ident(ref ident) => {
-            let label = ident.span().source_text().unwrap();
+            let label = ident.to_string();
             if label.len() == 1 {
                 let name: Option<Cow<'static, str>> = match label.chars().next().unwrap() {
                     'A'..='Z' => Some(label.into()),
@@ -440,7 +817,7 @@ fn get_key(tree: TokenTree) -> Option<TokenStream> {
 fn get_key_raw(tree: TokenTree) -> Option<Result<char, Cow<'static, str>>> {
     match tree {
         TokenTree::Literal(ref literal) => {
-            let x = literal.span().source_text().unwrap();
+            let x = literal.to_string();
             if x.len() == 1 {
                 Some(Ok(x.chars().next().unwrap()))
             } else {
@@ -455,22 +832,64 @@ fn get_key_raw(tree: TokenTree) -> Option<Result<char, Cow<'static, str>>> {
         }
         TokenTree::Punct(ref punct) => Some(Ok(punct.as_char())),
         TokenTree::Ident(ref ident) => {
-            let label = ident.span().source_text().unwrap();
+            let label = ident.to_string();
             if label.len() == 1 {
                 Some(Ok(label.chars().next().unwrap()))
-            } else {
+            } else if NAMED_KEYS.contains(&label.as_str()) {
                 Some(Err(label.into()))
+            } else {
+                abort!(ident, "Unknown key name `{}`", label);
             }
         }
         _ => None,
     }
 }
 
+/// Return the tokens of the `required` modifiers with any `optional` ones.
 #[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
-fn read_modifiers<F: Fn(u8) -> TokenStream>(
-    input: TokenStream,
+fn modifier_tokens<F: Fn(u8) -> TokenStream>(
+    required: u8,
+    optional: u8,
     to_modifiers: F,
-) -> (TokenStream, TokenStream) {
+) -> TokenStream {
+    // Optional modifiers expand to a match-or of every combination with a
+    // leading `|`, e.g.,
+    //
+    //    Ctrl-Shift?-A -> (| Modifiers(1) | Modifiers(5), KeyCode::KeyA).
+    //
+    // The leading `|` is allowed in a pattern, but in an expression it begins
+    // a closure with a refutable argument, a compile error, rather than
+    // silently or-ing the modifiers into `Ctrl-Shift`.
+    if optional == 0 {
+        return to_modifiers(required);
+    }
+    let alternatives = modifier_combinations(required, optional)
+        .into_iter()
+        .map(to_modifiers);
+    quote! { #(| #alternatives)* }
+}
+
+/// Return every combination of `required` with a subset of `optional` in
+/// ascending order.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn modifier_combinations(required: u8, optional: u8) -> Vec<u8> {
+    let mut result = vec![];
+    let mut subset = optional;
+    loop {
+        result.push(required | subset);
+        if subset == 0 {
+            break;
+        }
+        subset = (subset - 1) & optional;
+    }
+    result.sort();
+    result
+}
+
+/// Read the modifiers and return `(required, optional, rest)` where `required`
+/// and `optional` are bit flags.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_modifier_bits(input: TokenStream) -> (u8, u8, TokenStream) {
     let mut i = input.into_iter().peekable();
     let mut last_tree = None;
 
@@ -485,35 +904,46 @@ fn read_modifiers<F: Fn(u8) -> TokenStream>(
             _ => false,
         }
     }
+
+    fn is_question(tree: Option<&TokenTree>) -> bool {
+        matches!(tree, Some(TokenTree::Punct(ref punct)) if punct.as_char() == '?')
+    }
     let mut bitflags: u8 = 0;
+    let mut optional: u8 = 0;
 
-    let mut accum_mods = |modifier: Modifier| {
+    let mut accum_mods = |modifier: Modifier, is_optional: bool| {
         let bitflag = modifier.bitflag();
-        if bitflag < bitflags {
-            // emit_warning!(gcc
-            // emit_call_site_warning!("Modifiers must occur in this order: control, Alt, Shift, Super.");
-            if cfg!(feature = "strict-order") {
-                abort_call_site!("Modifiers must occur in this order: control, Alt, Shift, Super.");
-            } else {
-                emit_call_site_warning!(
-                    "Modifiers must occur in this order: control, Alt, Shift, Super."
-                );
-            }
+        check_modifier_order(bitflag, bitflags | optional);
+        if is_optional {
+            optional |= bitflag;
+        } else {
+            bitflags |= bitflag;
         }
-        bitflags |= bitflag;
     };
 
     while let Some(tree) = i.next() {
-        if i.peek().is_none() || (!is_dash(&tree) && !is_dash(i.peek().unwrap())) {
+        // An optional modifier is written with a question mark, e.g., `Shift?-`.
+        let is_optional = matches!(tree, TokenTree::Ident(_)) && is_question(i.peek());
+        if is_optional {
+            let question = i.next().unwrap();
+            if !i.peek().map(is_dash).unwrap_or(false) {
+                abort!(
+                    question,
+                    "An optional modifier must be followed by a hyphen"
+                );
+            }
+        }
+        if !is_optional && (i.peek().is_none() || (!is_dash(&tree) && !is_dash(i.peek().unwrap())))
+        {
             last_tree = Some(tree);
             break;
         } else {
             match tree {
                 TokenTree::Ident(ref ident) => match ident.span().source_text().unwrap().as_str() {
-                    "Ctrl" => accum_mods(Modifier::Control),
-                    "Alt" => accum_mods(Modifier::Alt),
-                    "Shift" => accum_mods(Modifier::Shift),
-                    "Super" => accum_mods(Modifier::Super),
+                    "Ctrl" => accum_mods(Modifier::Control, is_optional),
+                    "Alt" => accum_mods(Modifier::Alt, is_optional),
+                    "Shift" => accum_mods(Modifier::Shift, is_optional),
+                    "Super" => accum_mods(Modifier::Super, is_optional),
                     x => abort!(x, "Should be a modifier or a hyphen"),
                 },
                 TokenTree::Punct(ref punct) => match punct.as_char() {
@@ -527,18 +957,9 @@ fn read_modifiers<F: Fn(u8) -> TokenStream>(
             };
         }
     }
-    // This will add an empty to finish the expression:
-    //
-    //    Ctrl-Alt-EMPTY -> Control | Alt | EMPTY.
-    //
-    //  And it will provide a valid Modifier when none have been provided.
-    // r.extend([to_modifiers(Modifier::None)]);
-
-    // let x = proc_macro2::Literal::u8_suffixed(bitflags);
     (
-        // r,
-        to_modifiers(bitflags),
-        // quote! { ::keyseq::Modifiers(#x) },
+        bitflags,
+        optional,
         TokenStream::from_iter(last_tree.into_iter().chain(i)),
     )
 }
@@ -569,8 +990,29 @@ where
     F: Fn(u8) -> TokenStream,
     G: Fn(TokenTree) -> Option<TokenStream>,
 {
-    let (mods, input) = read_modifiers(input, to_modifiers);
-    let (key, rest) = read_key(input, get_key);
+    let (required, optional, input) = read_modifier_bits(input);
+    key_chord(
+        modifier_tokens(required, optional, to_modifiers),
+        input,
+        get_key,
+    )
+}
+
+/// Read the key after the modifiers `mods` and return the chord and the rest.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn key_chord<G>(mods: TokenStream, input: TokenStream, get_key: G) -> (TokenStream, TokenStream)
+where
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let (key, rest) = match read_key_range(input.clone()) {
+        Some((trees, rest)) => {
+            let keys = trees
+                .into_iter()
+                .map(|tree| get_key(tree).expect("No key found"));
+            (quote! { #(#keys)|* }, rest)
+        }
+        None => read_key(input, get_key),
+    };
     (
         quote! {
             (#mods, #key)
@@ -578,3 +1020,372 @@ where
         rest,
     )
 }
+
+/// Read a physical key chord. A chord of only modifiers, e.g., `Ctrl-Shift`,
+/// has no key to put in the tuple, so it is an error that points to the
+/// `pchord!` macros, whose `Chord` allows it.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_pkey_chord<F, G>(
+    input: TokenStream,
+    to_modifiers: F,
+    get_key: G,
+) -> (TokenStream, TokenStream)
+where
+    F: Fn(u8) -> TokenStream,
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let (required, optional, rest) = read_modifier_bits(input);
+    if let Some(TokenTree::Ident(ref ident)) = rest.clone().into_iter().next() {
+        if modifier_from_name(&ident.to_string()).is_some() {
+            abort!(
+                ident,
+                "A chord of only modifiers has no key; use pchord! or pchordseq! for it"
+            );
+        }
+    }
+    key_chord(
+        modifier_tokens(required, optional, to_modifiers),
+        rest,
+        get_key,
+    )
+}
+
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_pkey_chords<F, G>(mut input: TokenStream, to_modifiers: F, get_key: G) -> Vec<TokenStream>
+where
+    F: Fn(u8) -> TokenStream,
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let mut keys = vec![];
+
+    loop {
+        let (result, leftover) = read_pkey_chord(input, &to_modifiers, &get_key);
+        keys.push(result);
+        if leftover.is_empty() {
+            break;
+        }
+        input = leftover;
+    }
+    keys
+}
+
+/// Read a key range like `(1..=9)`, `F(1..=12)`, or `Numpad(0..10)` and return
+/// a token tree for each key in the range.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_key_range(input: TokenStream) -> Option<(Vec<TokenTree>, TokenStream)> {
+    let mut i = input.into_iter().peekable();
+    let prefix = match i.peek()? {
+        TokenTree::Ident(ident) => {
+            let prefix = ident.to_string();
+            if !matches!(prefix.as_str(), "Digit" | "F" | "Numpad") {
+                return None;
+            }
+            let ident = ident.clone();
+            i.next();
+            if !matches!(i.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+            {
+                return None;
+            }
+            Some(ident)
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => None,
+        _ => return None,
+    };
+    let Some(TokenTree::Group(group)) = i.next() else {
+        unreachable!();
+    };
+    let (start, end) = parse_range(group.stream())
+        .unwrap_or_else(|| abort!(group, "Expected a range like `1..=9`"));
+    let trees = (start..=end)
+        .map(|n| match prefix {
+            Some(ref ident) => match ident.to_string().as_str() {
+                "Digit" if n > 9 => abort!(group, "Digit range must be within 0..=9"),
+                "Numpad" if n > 9 => abort!(group, "Numpad range must be within 0..=9"),
+                "F" if n == 0 || n > 35 => abort!(group, "F range must be within 1..=35"),
+                "Digit" => TokenTree::Literal(Literal::u8_unsuffixed(n)),
+                x => TokenTree::Ident(Ident::new(&format!("{x}{n}"), ident.span())),
+            },
+            None if n > 9 => abort!(group, "Digit range must be within 0..=9"),
+            None => TokenTree::Literal(Literal::u8_unsuffixed(n)),
+        })
+        .collect();
+    Some((trees, TokenStream::from_iter(i)))
+}
+
+/// Parse `a..b` or `a..=b` into an inclusive range.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn parse_range(input: TokenStream) -> Option<(u8, u8)> {
+    let mut i = input.into_iter();
+    let start: u8 = match i.next()? {
+        TokenTree::Literal(literal) => literal.to_string().parse().ok()?,
+        _ => return None,
+    };
+    let mut dots = 0;
+    let mut inclusive = false;
+    let end: u8 = loop {
+        match i.next()? {
+            TokenTree::Punct(punct) if punct.as_char() == '.' && dots < 2 => dots += 1,
+            TokenTree::Punct(punct) if punct.as_char() == '=' && dots == 2 && !inclusive => {
+                inclusive = true
+            }
+            TokenTree::Literal(literal) if dots == 2 => break literal.to_string().parse().ok()?,
+            _ => return None,
+        }
+    };
+    if i.next().is_some() {
+        return None;
+    }
+    let end = if inclusive { end } else { end.checked_sub(1)? };
+    (start <= end).then_some((start, end))
+}
+
+/// Read a chord for the `pchord!` and `lchord!` macros. Repeating a chord,
+/// e.g., `Shift Shift`, is short hand for `DoubleTap(Shift)`.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_one_chord<G>(
+    input: TokenStream,
+    get_key: G,
+    key_type: TokenStream,
+    seq_name: &str,
+) -> TokenStream
+where
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let (result, leftover) = read_chord(input.clone(), &get_key, &key_type);
+    if leftover.is_empty() {
+        return result;
+    }
+    let consumed = input.clone().into_iter().count() - leftover.clone().into_iter().count();
+    let first = TokenStream::from_iter(input.into_iter().take(consumed));
+    if first.to_string() != leftover.to_string() {
+        abort!(
+            leftover,
+            "Too many tokens; use {}! for multiple keys",
+            seq_name
+        );
+    }
+    if has_press_qualifier(&leftover) {
+        abort!(
+            leftover,
+            "Only an unqualified chord may be repeated for a double tap"
+        );
+    }
+    let (mods, optional, key, _) = read_chord_parts(leftover, &get_key);
+    chord_tokens(
+        mods,
+        optional,
+        key,
+        quote! { _keyseq::Press::DoubleTap },
+        &key_type,
+    )
+}
+
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn has_press_qualifier(input: &TokenStream) -> bool {
+    let mut i = input.clone().into_iter();
+    match (i.next(), i.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group))) => {
+            group.delimiter() == Delimiter::Parenthesis
+                && matches!(
+                    ident.to_string().as_str(),
+                    "Tap" | "DoubleTap" | "Hold" | "Release"
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Read a chord, which may be qualified by how it is pressed, e.g.,
+/// `Tap(Ctrl)`, `DoubleTap(Shift)`, `Hold(Space)`, `Hold(Space, 750)`, or
+/// `Release(Ctrl-A)`.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_chord<G>(
+    input: TokenStream,
+    get_key: G,
+    key_type: &TokenStream,
+) -> (TokenStream, TokenStream)
+where
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    if !has_press_qualifier(&input) {
+        let (mods, optional, key, rest) = read_chord_parts(input, get_key);
+        // A chord of only modifiers is tapped by default.
+        let press = if key.is_none() {
+            quote! { _keyseq::Press::Tap }
+        } else {
+            quote! { _keyseq::Press::Press }
+        };
+        return (chord_tokens(mods, optional, key, press, key_type), rest);
+    }
+    let mut i = input.into_iter();
+    let (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group))) = (i.next(), i.next())
+    else {
+        unreachable!();
+    };
+    // Split the arguments at commas.
+    let mut args = vec![TokenStream::new()];
+    for tree in group.stream() {
+        match tree {
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => args.push(TokenStream::new()),
+            tree => args.last_mut().unwrap().extend([tree]),
+        }
+    }
+    let mut args = args.into_iter();
+    let chord = args.next().unwrap();
+    let press = match ident.to_string().as_str() {
+        "Tap" => quote! { _keyseq::Press::Tap },
+        "DoubleTap" => quote! { _keyseq::Press::DoubleTap },
+        "Release" => quote! { _keyseq::Press::Release },
+        _ => match args.next() {
+            Some(millis) => {
+                let Ok(millis) = millis.to_string().parse::<u64>() else {
+                    abort!(millis, "Expected a hold duration in milliseconds");
+                };
+                let millis = Literal::u64_suffixed(millis);
+                quote! { _keyseq::Press::Hold(::core::time::Duration::from_millis(#millis)) }
+            }
+            None => quote! { _keyseq::Press::Hold(_keyseq::Press::DEFAULT_HOLD) },
+        },
+    };
+    if let Some(extra) = args.next() {
+        abort!(extra, "Unexpected argument");
+    }
+    let (mods, optional, key, leftover) = read_chord_parts(chord, get_key);
+    if !leftover.is_empty() {
+        abort!(leftover, "Expected one chord");
+    }
+    (
+        chord_tokens(mods, optional, key, press, key_type),
+        TokenStream::from_iter(i),
+    )
+}
+
+/// Read modifiers and a key, returning `(required, optional, key, rest)`. The
+/// key is `None` when the chord ends with a modifier, e.g., `Ctrl-Shift`.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_chord_parts<G>(input: TokenStream, get_key: G) -> (u8, u8, Option<TokenStream>, TokenStream)
+where
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let (mut mods, optional, input) = read_modifier_bits(input);
+    let mut i = input.clone().into_iter();
+    if let Some(TokenTree::Ident(ref ident)) = i.next() {
+        if let Some(modifier) = modifier_from_name(&ident.to_string()) {
+            check_modifier_order(modifier.bitflag(), mods | optional);
+            mods |= modifier.bitflag();
+            return (mods, optional, None, TokenStream::from_iter(i));
+        }
+    }
+    let (key, rest) = read_key(input, get_key);
+    (mods, optional, Some(key), rest)
+}
+
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn chord_tokens(
+    mods: u8,
+    optional: u8,
+    key: Option<TokenStream>,
+    press: TokenStream,
+    key_type: &TokenStream,
+) -> TokenStream {
+    let mods = to_keyseq_modifiers(mods);
+    let optional = to_keyseq_modifiers(optional);
+    let key = match key {
+        Some(key) => quote! { ::core::option::Option::Some(#key) },
+        None => quote! { ::core::option::Option::<#key_type>::None },
+    };
+    quote! {
+        _keyseq::Chord { mods: #mods, optional: #optional, key: #key, press: #press }
+    }
+}
+
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn modifier_from_name(name: &str) -> Option<Modifier> {
+    match name {
+        "Ctrl" => Some(Modifier::Control),
+        "Alt" => Some(Modifier::Alt),
+        "Shift" => Some(Modifier::Shift),
+        "Super" => Some(Modifier::Super),
+        _ => None,
+    }
+}
+
+/// Abort or warn if `bitflag` comes before any of the `bitflags` seen so far.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn check_modifier_order(bitflag: u8, bitflags: u8) {
+    if bitflag < bitflags {
+        if cfg!(feature = "strict-order") {
+            abort_call_site!("Modifiers must occur in this order: control, Alt, Shift, Super.");
+        } else {
+            emit_call_site_warning!(
+                "Modifiers must occur in this order: control, Alt, Shift, Super."
+            );
+        }
+    }
+}
+
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_chords<G>(mut input: TokenStream, get_key: G, key_type: TokenStream) -> Vec<TokenStream>
+where
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    let mut chords = vec![];
+
+    loop {
+        let (result, leftover) = read_chord(input, &get_key, &key_type);
+        chords.push(result);
+        if leftover.is_empty() {
+            break;
+        }
+        input = leftover;
+    }
+    chords
+}
+
+/// Read a combo like `Ctrl-J+K`: modifiers followed by keys joined with plus.
+#[cfg(any(feature = "winit", feature = "bevy", feature = "poor"))]
+fn read_combo<F, G>(input: TokenStream, to_modifiers: F, get_key: G) -> TokenStream
+where
+    F: Fn(u8) -> TokenStream,
+    G: Fn(TokenTree) -> Option<TokenStream>,
+{
+    fn is_punct(tree: Option<&TokenTree>, c: char) -> bool {
+        matches!(tree, Some(TokenTree::Punct(ref punct)) if punct.as_char() == c)
+    }
+    let mut i = input.into_iter().peekable();
+    let mut bitflags: u8 = 0;
+    // Modifiers are only recognized before the first key, so `Ctrl+J+K` works
+    // with the "permit-plus" feature too.
+    while let Some(TokenTree::Ident(ident)) = i.peek() {
+        let Some(modifier) = modifier_from_name(&ident.to_string()) else {
+            break;
+        };
+        let ident = i.next().unwrap();
+        if !(is_punct(i.peek(), '-') || (cfg!(feature = "permit-plus") && is_punct(i.peek(), '+')))
+        {
+            abort!(ident, "Should be a modifier followed by a hyphen");
+        }
+        i.next();
+        check_modifier_order(modifier.bitflag(), bitflags);
+        bitflags |= modifier.bitflag();
+    }
+    let mut keys = vec![];
+    loop {
+        let tree = i
+            .next()
+            .unwrap_or_else(|| abort_call_site!("Expected a key"));
+        keys.push(get_key(tree).expect("No key found"));
+        match i.next() {
+            None => break,
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '+' => {}
+            Some(x) => abort!(x, "Keys in a combo must be joined with a plus"),
+        }
+    }
+    if keys.len() < 2 {
+        abort_call_site!("A combo must have at least two keys; use pkey! for one key");
+    }
+    let mods = to_modifiers(bitflags);
+    quote! {
+        (#mods, [#(#keys),*])
+    }
+}
@@ -1,8 +1,46 @@
 //! keyseq macros for bevy game engine
-use super::Modifiers;
+use super::{
+    capture::ChordCapture,
+    combo::ComboDetector,
+    keymap::{self, Continuation, KeySeq, Leader},
+    layout::{self, Layout},
+    modal::ModalKeymap,
+    modifier_chord::ModifierChordRecognizer,
+    press::PressRecognizer,
+    release::ReleaseTracker,
+    repeat::RepeatMatcher,
+    Modifiers,
+};
 use ::bevy::{
-    input::{keyboard::KeyCode, ButtonInput},
-    prelude::Res,
+    app::{App, Plugin, PreUpdate},
+    ecs::{
+        change_detection::DetectChanges,
+        hierarchy::ChildOf,
+        message::{Message, MessageWriter},
+        prelude::{Component, Entity, Query, Resource, With},
+        schedule::IntoScheduleConfigs,
+    },
+    input::{
+        keyboard::{Key, KeyCode, KeyboardInput, NativeKeyCode},
+        ButtonInput, ButtonState, InputSystems,
+    },
+    input_focus::InputFocus,
+    prelude::{Res, ResMut},
+    state::state::{FreelyMutableState, NextState, State},
+    time::Time,
+};
+use std::marker::PhantomData;
+use std::time::Duration;
+#[cfg(feature = "which-key")]
+use {
+    super::display::Style,
+    ::bevy::{
+        app::Update,
+        color::Color,
+        ecs::{prelude::Local, system::Commands},
+        ui::{widget::Text, BackgroundColor, Node, PositionType, UiRect, Val},
+    },
+    std::fmt,
 };
 
 impl Modifiers {
@@ -47,6 +85,993 @@ impl From<&ButtonInput<KeyCode>> for Modifiers {
     }
 }
 
+/// Return the numeric index of a `Digit*`, `F*`, or `Numpad*` key.
+///
+/// This pairs with key ranges like `pkey! { Alt-(1..=9) }` to find which key
+/// matched.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, bevy::{key_index, pkey}};
+/// use bevy::input::keyboard::KeyCode;
+/// let select_tab = |chord: (Modifiers, KeyCode)| match chord {
+///     pkey! { Alt-(1..=9) } => key_index(&chord.1),
+///     _ => None,
+/// };
+/// assert_eq!(select_tab((Modifiers::ALT, KeyCode::Digit3)), Some(3));
+/// assert_eq!(select_tab((Modifiers::ALT, KeyCode::Digit0)), None);
+/// assert_eq!(key_index(&KeyCode::F12), Some(12));
+/// assert_eq!(key_index(&KeyCode::Numpad7), Some(7));
+/// assert_eq!(key_index(&KeyCode::KeyA), None);
+/// ```
+pub fn key_index(key: &KeyCode) -> Option<u8> {
+    key_index!(KeyCode, key)
+}
+
+/// The physical keys of [layout::CODES] in the same order.
+const LAYOUT_KEYS: [KeyCode; 49] = layout_keys!(KeyCode);
+
+/// Return the W3C code of `key` if a [Layout] covers it.
+pub fn code_name(key: &KeyCode) -> Option<&'static str> {
+    LAYOUT_KEYS
+        .iter()
+        .position(|k| k == key)
+        .map(|index| layout::CODES[index])
+}
+
+/// The leader placeholder is an unidentified key with XKB code 0, which no
+/// keyboard reports since XKB codes start at 8.
+impl Leader for KeyCode {
+    fn leader() -> Self {
+        KeyCode::Unidentified(NativeKeyCode::Xkb(0))
+    }
+}
+
+/// Return the name of `key` in keyseq's notation, e.g., "S", "[", or
+/// "ArrowUp", see [notation](crate::notation). Printable keys are named by the
+/// character they type on [Layout::UsQwerty] and the [Leader] placeholder is
+/// "Leader".
+///
+/// ```
+/// use bevy::input::keyboard::KeyCode;
+/// use keyseq::{bevy::key_name, keymap::Leader};
+/// assert_eq!(key_name(&KeyCode::KeyS), "S");
+/// assert_eq!(key_name(&KeyCode::BracketLeft), "[");
+/// assert_eq!(key_name(&KeyCode::Space), "Space");
+/// assert_eq!(key_name(&KeyCode::ArrowUp), "ArrowUp");
+/// assert_eq!(key_name(&KeyCode::leader()), "Leader");
+/// ```
+pub fn key_name(key: &KeyCode) -> String {
+    if *key == KeyCode::leader() {
+        return String::leader();
+    }
+    match code_name(key).and_then(|code| Layout::UsQwerty.to_logical(Modifiers::NONE, code)) {
+        Some((_, ' ')) => "Space".to_string(),
+        Some((_, ch)) => ch.to_uppercase().collect(),
+        None => format!("{key:?}"),
+    }
+}
+
+/// Return the logical chord typed by the physical chord `mods` and `key` on
+/// `layout`. See [Layout::to_logical].
+///
+/// ```
+/// use keyseq::{_keyseq, layout::Layout, bevy::{pkey, lkey, to_logical}};
+/// let logical = |(mods, key)| to_logical(Layout::FrenchAzerty, mods, key);
+/// assert_eq!(logical(pkey! { Ctrl-Semicolon }), Some(lkey! { Ctrl-m }));
+/// assert_eq!(logical(pkey! { Shift-Q }), Some(lkey! { Shift-a }));
+/// assert_eq!(logical(pkey! { Shift-1 }), Some(lkey! { 1 }));
+/// assert_eq!(logical(pkey! { Space }), Some(lkey! { Space }));
+/// ```
+pub fn to_logical(layout: Layout, mods: Modifiers, key: KeyCode) -> Option<(Modifiers, Key)> {
+    let (mods, ch) = layout.to_logical(mods, code_name(&key)?)?;
+    Some((
+        mods,
+        if ch == ' ' {
+            Key::Space
+        } else {
+            Key::Character(ch.to_string().into())
+        },
+    ))
+}
+
+/// Return the physical chord that types the logical chord `mods` and `key` on
+/// `layout`, adding Shift if needed. See [Layout::to_physical].
+///
+/// ```
+/// use keyseq::{_keyseq, layout::Layout, bevy::{pkey, lkey, to_physical}};
+/// let physical = |(mods, key)| to_physical(Layout::GermanQwertz, mods, &key);
+/// assert_eq!(physical(lkey! { Ctrl-z }), Some(pkey! { Ctrl-Y }));
+/// assert_eq!(physical(lkey! { Shift-z }), Some(pkey! { Shift-Y }));
+/// assert_eq!(physical(lkey! { Z }), Some(pkey! { Shift-Y }));
+/// assert_eq!(physical(lkey! { Shift-Space }), Some(pkey! { Shift-Space }));
+/// ```
+pub fn to_physical(layout: Layout, mods: Modifiers, key: &Key) -> Option<(Modifiers, KeyCode)> {
+    let ch = match key {
+        Key::Space => ' ',
+        Key::Character(s) => {
+            let mut chars = s.chars();
+            chars.next().filter(|_| chars.next().is_none())?
+        }
+        _ => return None,
+    };
+    let (mods, code) = layout.to_physical(mods, ch)?;
+    let index = layout::CODES.iter().position(|c| *c == code)?;
+    Some((mods, LAYOUT_KEYS[index]))
+}
+
+/// Return true if the chord types text, i.e., a printable key with no
+/// modifiers or only Shift.
+///
+/// ```
+/// use bevy::input::keyboard::KeyCode;
+/// use keyseq::{Modifiers, bevy::is_text_chord};
+/// assert!(is_text_chord(Modifiers::NONE, &KeyCode::KeyG));
+/// assert!(is_text_chord(Modifiers::SHIFT, &KeyCode::Digit1));
+/// assert!(!is_text_chord(Modifiers::CONTROL, &KeyCode::KeyG));
+/// assert!(!is_text_chord(Modifiers::NONE, &KeyCode::F1));
+/// ```
+pub fn is_text_chord(mods: Modifiers, key: &KeyCode) -> bool {
+    (mods == Modifiers::NONE || mods == Modifiers::SHIFT)
+        && matches!(
+            key,
+            KeyCode::Digit0
+                | KeyCode::Digit1
+                | KeyCode::Digit2
+                | KeyCode::Digit3
+                | KeyCode::Digit4
+                | KeyCode::Digit5
+                | KeyCode::Digit6
+                | KeyCode::Digit7
+                | KeyCode::Digit8
+                | KeyCode::Digit9
+                | KeyCode::Numpad0
+                | KeyCode::Numpad1
+                | KeyCode::Numpad2
+                | KeyCode::Numpad3
+                | KeyCode::Numpad4
+                | KeyCode::Numpad5
+                | KeyCode::Numpad6
+                | KeyCode::Numpad7
+                | KeyCode::Numpad8
+                | KeyCode::Numpad9
+                | KeyCode::KeyA
+                | KeyCode::KeyB
+                | KeyCode::KeyC
+                | KeyCode::KeyD
+                | KeyCode::KeyE
+                | KeyCode::KeyF
+                | KeyCode::KeyG
+                | KeyCode::KeyH
+                | KeyCode::KeyI
+                | KeyCode::KeyJ
+                | KeyCode::KeyK
+                | KeyCode::KeyL
+                | KeyCode::KeyM
+                | KeyCode::KeyN
+                | KeyCode::KeyO
+                | KeyCode::KeyP
+                | KeyCode::KeyQ
+                | KeyCode::KeyR
+                | KeyCode::KeyS
+                | KeyCode::KeyT
+                | KeyCode::KeyU
+                | KeyCode::KeyV
+                | KeyCode::KeyW
+                | KeyCode::KeyX
+                | KeyCode::KeyY
+                | KeyCode::KeyZ
+                | KeyCode::Space
+                | KeyCode::Minus
+                | KeyCode::Equal
+                | KeyCode::BracketLeft
+                | KeyCode::BracketRight
+                | KeyCode::Backslash
+                | KeyCode::Semicolon
+                | KeyCode::Quote
+                | KeyCode::Backquote
+                | KeyCode::Comma
+                | KeyCode::Period
+                | KeyCode::Slash
+                | KeyCode::IntlBackslash
+                | KeyCode::IntlRo
+                | KeyCode::IntlYen
+                | KeyCode::NumpadAdd
+                | KeyCode::NumpadSubtract
+                | KeyCode::NumpadMultiply
+                | KeyCode::NumpadDivide
+                | KeyCode::NumpadDecimal
+                | KeyCode::NumpadComma
+                | KeyCode::NumpadEqual
+        )
+}
+
+impl ComboDetector<KeyCode> {
+    /// Read the key presses and releases from `input` at time `now`, which is
+    /// usually `Time::elapsed()`. Modifier keys are not part of combos.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{Modifiers, _keyseq, bevy::pcombo, combo::{ComboDetector, ComboEvent}};
+    /// let mut detector = ComboDetector::new(Duration::from_millis(50))
+    ///     .with(pcombo! { J+K });
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// input.press(KeyCode::KeyJ);
+    /// detector.read_input(&input, Duration::from_millis(0));
+    /// input.clear();
+    /// input.press(KeyCode::KeyK);
+    /// detector.read_input(&input, Duration::from_millis(16));
+    /// assert_eq!(detector.drain().collect::<Vec<_>>(), [ComboEvent::Combo(0)]);
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>, now: Duration) {
+        let mods = Modifiers::from(input);
+        // A key pressed and released within one frame is released last.
+        for key in input.get_just_released() {
+            if !input.just_pressed(*key) {
+                self.release(key, now);
+            }
+        }
+        for key in input.get_just_pressed() {
+            if Modifiers::from(*key).is_empty() {
+                self.press(mods, *key, now);
+            }
+        }
+        for key in input.get_just_released() {
+            if input.just_pressed(*key) {
+                self.release(key, now);
+            }
+        }
+        self.update(now);
+    }
+}
+
+impl PressRecognizer<KeyCode> {
+    /// Read the key presses and releases from `input` at time `now`, which is
+    /// usually `Time::elapsed()`.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{_keyseq, bevy::pchord, press::PressRecognizer};
+    /// let mut recognizer = PressRecognizer::default()
+    ///     .with(pchord! { Shift Shift })
+    ///     .with(pchord! { Hold(Space, 500) });
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// let ms = Duration::from_millis;
+    /// for (t, pressed) in [(0, true), (50, false), (100, true), (150, false)] {
+    ///     input.clear();
+    ///     if pressed {
+    ///         input.press(KeyCode::ShiftLeft);
+    ///     } else {
+    ///         input.release(KeyCode::ShiftLeft);
+    ///     }
+    ///     recognizer.read_input(&input, ms(t));
+    /// }
+    /// assert_eq!(recognizer.drain().collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>, now: Duration) {
+        let mods = Modifiers::from(input);
+        // A key pressed and released within one frame is released last.
+        for key in input.get_just_released() {
+            if !input.just_pressed(*key) {
+                self.release_key(*key, now);
+            }
+        }
+        for key in input.get_just_pressed() {
+            let modifier = Modifiers::from(*key);
+            if modifier.is_empty() {
+                self.press(mods, *key, now);
+            } else {
+                self.press_modifier(mods.difference(modifier), modifier, now);
+            }
+        }
+        for key in input.get_just_released() {
+            if input.just_pressed(*key) {
+                self.release_key(*key, now);
+            }
+        }
+        self.update(now);
+    }
+
+    fn release_key(&mut self, key: KeyCode, now: Duration) {
+        let modifier = Modifiers::from(key);
+        if modifier.is_empty() {
+            self.release(&key, now);
+        } else {
+            self.release_modifier(modifier, now);
+        }
+    }
+}
+
+impl ReleaseTracker<KeyCode> {
+    /// Read the key presses and releases from `input`. Modifier keys are
+    /// ignored.
+    ///
+    /// ```
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{Modifiers, _keyseq, bevy::{pkey, pkeyseq}, release::ReleaseTracker};
+    /// let mut tracker = ReleaseTracker::default();
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// input.press(KeyCode::ControlLeft);
+    /// input.press(KeyCode::Space);
+    /// tracker.read_input(&input);
+    /// input.clear();
+    /// // Let go of Ctrl first.
+    /// input.release(KeyCode::ControlLeft);
+    /// tracker.read_input(&input);
+    /// input.clear();
+    /// input.release(KeyCode::Space);
+    /// tracker.read_input(&input);
+    /// let released: Vec<_> = tracker.drain().collect();
+    /// assert!(matches!(released[..], [pkey! { Ctrl-Space }]));
+    /// assert!(tracker.ends_with(&pkeyseq! { Ctrl-Space }));
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>) {
+        let mods = Modifiers::from(input);
+        for key in input.get_just_pressed() {
+            if Modifiers::from(*key).is_empty() {
+                self.press(mods, *key);
+            }
+        }
+        for key in input.get_just_released() {
+            self.release(key);
+        }
+    }
+}
+
+impl RepeatMatcher<KeyCode> {
+    /// Read the key presses and releases from `input` at time `now`, which is
+    /// usually `Time::elapsed()`. `ButtonInput` does not report OS repeats, so
+    /// [RepeatPolicy::Os](crate::repeat::RepeatPolicy::Os) bindings only fire on
+    /// the press; use [keyboard_input](RepeatMatcher::keyboard_input) for those.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{_keyseq, bevy::pchord, repeat::{RepeatMatcher, RepeatPolicy}};
+    /// let ms = Duration::from_millis;
+    /// let mut matcher = RepeatMatcher::default().with(
+    ///     pchord! { Ctrl-ArrowRight },
+    ///     RepeatPolicy::Custom { delay: ms(300), interval: ms(50) },
+    /// );
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// input.press(KeyCode::ControlLeft);
+    /// input.press(KeyCode::ArrowRight);
+    /// matcher.read_input(&input, ms(0));
+    /// input.clear();
+    /// matcher.read_input(&input, ms(400));
+    /// // The press and one repeat, not every repeat since 300 ms.
+    /// assert_eq!(matcher.drain().count(), 2);
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>, now: Duration) {
+        let mods = Modifiers::from(input);
+        for key in input.get_just_pressed() {
+            if Modifiers::from(*key).is_empty() {
+                self.press(mods, *key, now);
+            }
+        }
+        for key in input.get_just_released() {
+            self.release(key, now);
+        }
+        self.update(now);
+    }
+
+    /// Read a keyboard input message with the current modifiers at time `now`.
+    /// Unlike [read_input](RepeatMatcher::read_input), this sees OS repeats.
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy::input::keyboard::KeyboardInput;
+    /// use keyseq::{Modifiers, repeat::RepeatMatcher};
+    ///
+    /// fn keyboard_system(mut matcher: Local<RepeatMatcher<KeyCode>>,
+    ///                    input: Res<ButtonInput<KeyCode>>,
+    ///                    mut messages: MessageReader<KeyboardInput>,
+    ///                    time: Res<Time>) {
+    ///     let mods = Modifiers::from(&input);
+    ///     for message in messages.read() {
+    ///         matcher.keyboard_input(mods, message, time.elapsed());
+    ///     }
+    ///     matcher.update(time.elapsed());
+    ///     for index in matcher.drain() {
+    ///         println!("binding {index} fired");
+    ///     }
+    /// }
+    /// ```
+    pub fn keyboard_input(&mut self, mods: Modifiers, event: &KeyboardInput, now: Duration) {
+        let key = event.key_code;
+        if !Modifiers::from(key).is_empty() {
+            return;
+        }
+        match event.state {
+            ButtonState::Pressed if event.repeat => self.os_repeat(&key, now),
+            ButtonState::Pressed => self.press(mods, key, now),
+            ButtonState::Released => self.release(&key, now),
+        }
+    }
+}
+
+impl<M: Clone + PartialEq, A: Clone> ModalKeymap<M, KeyCode, A> {
+    /// Read the key presses from `input`. Modifier keys are ignored.
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>) {
+        for (mods, key) in just_pressed_chords(input, false) {
+            self.press(mods, key);
+        }
+    }
+}
+
+/// Read key presses into a [ModalKeymap] resource whose mode follows the state
+/// `S`.
+///
+/// When the state changes, the keymap follows. When a binding switches modes,
+/// the state follows on the next state transition. Drain the keymap's events in
+/// a system that runs after this one. Text chords are ignored while a
+/// [TextSink] has focus.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use keyseq::{_keyseq, bevy::{pkeyseq, read_modal_keymap}, modal::{ModalEvent, ModalKeymap}};
+///
+/// #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// enum Mode { #[default] Normal, Insert }
+///
+/// fn actions(mut keymap: ResMut<ModalKeymap<Mode, KeyCode, &'static str>>) {
+///     for event in keymap.drain() {
+///         if let ModalEvent::Action(action) = event {
+///             println!("{action}");
+///         }
+///     }
+/// }
+///
+/// let mut keymap = ModalKeymap::new(Mode::Normal);
+/// keymap.bind(Mode::Normal, pkeyseq! { D D }, "delete-line");
+/// keymap.switch(Mode::Normal, pkeyseq! { I }, Mode::Insert);
+/// keymap.switch(Mode::Insert, pkeyseq! { Escape }, Mode::Normal);
+/// keymap.set_passthrough(Mode::Insert, true);
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .init_state::<Mode>()
+///     .insert_resource(keymap)
+///     .add_systems(Update, (read_modal_keymap::<Mode, &'static str>, actions).chain())
+///     .run();
+/// ```
+pub fn read_modal_keymap<S: FreelyMutableState, A: Clone + Send + Sync + 'static>(
+    state: Res<State<S>>,
+    mut next_state: ResMut<NextState<S>>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut keymap: ResMut<ModalKeymap<S, KeyCode, A>>,
+    focus: Option<Res<InputFocus>>,
+    sinks: Query<(), With<TextSink>>,
+) {
+    if state.is_changed() && keymap.current() != state.get() {
+        keymap.set_mode(state.get().clone());
+    }
+    let suppress_text = focus
+        .and_then(|focus| focus.get())
+        .is_some_and(|e| sinks.contains(e));
+    for (mods, key) in just_pressed_chords(&input, suppress_text) {
+        keymap.press(mods, key);
+    }
+    if keymap.current() != state.get() {
+        next_state.set(keymap.current().clone());
+    }
+}
+
+/// Marks an entity that consumes typed text, e.g., a text field.
+///
+/// While it has [InputFocus], text chords (see [is_text_chord]) do not reach
+/// [read_key_bindings] or [read_modal_keymap], so typing "g g" in a text field
+/// does not fire `pkeyseq! { G G }`. Chords with other modifiers, e.g.,
+/// `Ctrl-G`, still do.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct TextSink;
+
+/// Return the non-modifier chords just pressed, without text chords if
+/// `suppress_text` is true.
+fn just_pressed_chords(
+    input: &ButtonInput<KeyCode>,
+    suppress_text: bool,
+) -> impl Iterator<Item = (Modifiers, KeyCode)> + '_ {
+    let mods = Modifiers::from(input);
+    input
+        .get_just_pressed()
+        .filter(|key| Modifiers::from(**key).is_empty())
+        .filter(move |key| !(suppress_text && is_text_chord(mods, key)))
+        .map(move |key| (mods, *key))
+}
+
+/// Key bindings that apply while this entity or one of its descendants has
+/// input focus.
+///
+/// Bindings are resolved along the focus chain: the focused entity first, then
+/// its ancestors, then the [GlobalKeyBindings]. The first scope that binds a
+/// key sequence, or a longer sequence that starts with it, wins. Add
+/// [KeyBindingsPlugin] to resolve them into [KeyBindingAction] messages.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use keyseq::{_keyseq, bevy::{pkeyseq, KeyBindings}};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Action { Find, Filter }
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn(KeyBindings::new().with(pkeyseq! { Ctrl-F }, Action::Find));
+///     commands.spawn(KeyBindings::new().with(pkeyseq! { Ctrl-F }, Action::Filter));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct KeyBindings<A: Send + Sync + 'static> {
+    bindings: Vec<(KeySeq<KeyCode>, A)>,
+}
+
+impl<A: Send + Sync + 'static> Default for KeyBindings<A> {
+    fn default() -> Self {
+        KeyBindings { bindings: vec![] }
+    }
+}
+
+impl<A: Send + Sync + 'static> KeyBindings<A> {
+    /// Create empty key bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `keys` to `action`, replacing any previous binding of `keys`.
+    pub fn bind(&mut self, keys: impl IntoIterator<Item = (Modifiers, KeyCode)>, action: A) {
+        let keys: KeySeq<KeyCode> = keys.into_iter().collect();
+        self.bindings.retain(|(k, _)| *k != keys);
+        self.bindings.push((keys, action));
+    }
+
+    /// Bind `keys` to `action` and return self.
+    pub fn with(mut self, keys: impl IntoIterator<Item = (Modifiers, KeyCode)>, action: A) -> Self {
+        self.bind(keys, action);
+        self
+    }
+
+    /// Return the action bound to `seq`.
+    pub fn lookup(&self, seq: &[(Modifiers, KeyCode)]) -> Option<&A> {
+        self.bindings
+            .iter()
+            .find(|(keys, _)| keys.as_slice() == seq)
+            .map(|(_, action)| action)
+    }
+
+    /// Return true if `seq` is the start of a longer bound key sequence.
+    pub fn is_prefix(&self, seq: &[(Modifiers, KeyCode)]) -> bool {
+        self.bindings
+            .iter()
+            .any(|(keys, _)| keys.len() > seq.len() && keys.starts_with(seq))
+    }
+
+    /// Return the chords that may follow `prefix`, see
+    /// [Keymap::continuations](crate::keymap::Keymap::continuations).
+    pub fn continuations(&self, prefix: &[(Modifiers, KeyCode)]) -> Vec<Continuation<KeyCode, &A>> {
+        keymap::continuations(self.iter(), prefix, |start, prefix| start == prefix)
+    }
+
+    /// Return the key sequences and their actions.
+    pub fn iter(&self) -> impl Iterator<Item = (&[(Modifiers, KeyCode)], &A)> {
+        self.bindings
+            .iter()
+            .map(|(keys, action)| (keys.as_slice(), action))
+    }
+}
+
+/// The key bindings that apply when no focused scope binds a key sequence.
+#[derive(Resource, Clone, Debug)]
+pub struct GlobalKeyBindings<A: Send + Sync + 'static>(pub KeyBindings<A>);
+
+impl<A: Send + Sync + 'static> Default for GlobalKeyBindings<A> {
+    fn default() -> Self {
+        GlobalKeyBindings(KeyBindings::default())
+    }
+}
+
+/// A key binding fired.
+#[derive(Message, Clone, Debug, PartialEq)]
+pub struct KeyBindingAction<A: Send + Sync + 'static> {
+    /// The action that was bound.
+    pub action: A,
+    /// The entity whose [KeyBindings] matched or `None` for global bindings.
+    pub entity: Option<Entity>,
+}
+
+/// Resolves [KeyBindings] along the focus chain and writes
+/// [KeyBindingAction] messages.
+pub struct KeyBindingsPlugin<A>(PhantomData<A>);
+
+impl<A> Default for KeyBindingsPlugin<A> {
+    fn default() -> Self {
+        KeyBindingsPlugin(PhantomData)
+    }
+}
+
+impl<A: Clone + Send + Sync + 'static> Plugin for KeyBindingsPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_message::<KeyBindingAction<A>>()
+            .init_resource::<GlobalKeyBindings<A>>()
+            .init_resource::<KeySequenceTimeout>()
+            .init_resource::<PendingKeys<A>>()
+            .add_systems(PreUpdate, read_key_bindings::<A>.after(InputSystems));
+    }
+}
+
+/// How long [read_key_bindings] waits for the next chord of a sequence. When
+/// it runs out, the longest sequence typed that is bound fires, e.g., `G` when
+/// both `G` and `G G` are bound, and the rest is dropped. `None` waits forever
+/// as Emacs does, which suits a [WhichKeyPlugin] overlay; Vim waits a second.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use keyseq::bevy::KeySequenceTimeout;
+/// use std::time::Duration;
+///
+/// fn setup(mut timeout: ResMut<KeySequenceTimeout>) {
+///     timeout.0 = Some(Duration::from_secs(1));
+/// }
+/// ```
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeySequenceTimeout(pub Option<Duration>);
+
+/// The key sequence in progress for [read_key_bindings].
+#[derive(Resource, Debug)]
+pub struct PendingKeys<A> {
+    keys: KeySeq<KeyCode>,
+    focus: Option<Entity>,
+    /// When the last chord was typed.
+    last: Duration,
+    action: PhantomData<A>,
+}
+
+impl<A> Default for PendingKeys<A> {
+    fn default() -> Self {
+        PendingKeys {
+            keys: vec![],
+            focus: None,
+            last: Duration::ZERO,
+            action: PhantomData,
+        }
+    }
+}
+
+impl<A> PendingKeys<A> {
+    /// Return the chords typed so far.
+    pub fn keys(&self) -> &[(Modifiers, KeyCode)] {
+        &self.keys
+    }
+
+    /// Return the entity that had focus when the sequence began.
+    pub fn focus(&self) -> Option<Entity> {
+        self.focus
+    }
+}
+
+/// Resolve key presses against [KeyBindings] along the focus chain given by
+/// the [InputFocus] resource, then [GlobalKeyBindings]. Text chords are
+/// ignored while a [TextSink] has focus. A sequence in progress ends after the
+/// [KeySequenceTimeout] or when a key breaks it, and the longest bound start
+/// of it fires.
+#[allow(clippy::too_many_arguments)]
+pub fn read_key_bindings<A: Clone + Send + Sync + 'static>(
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    timeout: Res<KeySequenceTimeout>,
+    focus: Option<Res<InputFocus>>,
+    scopes: Query<&KeyBindings<A>>,
+    parents: Query<&ChildOf>,
+    sinks: Query<(), With<TextSink>>,
+    global: Option<Res<GlobalKeyBindings<A>>>,
+    mut pending: ResMut<PendingKeys<A>>,
+    mut writer: MessageWriter<KeyBindingAction<A>>,
+) {
+    let focused = focus.and_then(|focus| focus.get());
+    if pending.focus != focused {
+        // A sequence does not survive a change of focus.
+        pending.keys.clear();
+        pending.focus = focused;
+    }
+    let chain = focus_chain(focused, &scopes, &parents, global.as_deref());
+    let now = time.elapsed();
+    if timeout
+        .0
+        .is_some_and(|timeout| now.saturating_sub(pending.last) >= timeout)
+    {
+        flush_chain(&chain, &mut pending.keys, &mut writer);
+    }
+    let suppress_text = focused.is_some_and(|e| sinks.contains(e));
+    for chord in just_pressed_chords(&input, suppress_text) {
+        pending.last = now;
+        pending.keys.push(chord);
+        while !pending.keys.is_empty() {
+            match resolve_chain(&chain, &pending.keys) {
+                Resolution::Fire(entity, action) => {
+                    writer.write(KeyBindingAction {
+                        action: action.clone(),
+                        entity,
+                    });
+                    pending.keys.clear();
+                }
+                Resolution::Wait => break,
+                Resolution::None => {
+                    // Fire what is bound before the last key, which may start
+                    // a sequence of its own.
+                    let retry = pending.keys.len() > 1;
+                    let last = pending.keys.pop();
+                    flush_chain(&chain, &mut pending.keys, &mut writer);
+                    if let Some(last) = last.filter(|_| retry) {
+                        pending.keys.push(last);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Return the [KeyBindings] of `focused` and its ancestors, then the global
+/// bindings.
+fn focus_chain<'a, A: Send + Sync + 'static>(
+    focused: Option<Entity>,
+    scopes: &'a Query<&KeyBindings<A>>,
+    parents: &Query<&ChildOf>,
+    global: Option<&'a GlobalKeyBindings<A>>,
+) -> Vec<(Option<Entity>, &'a KeyBindings<A>)> {
+    let mut chain = vec![];
+    let mut entity = focused;
+    while let Some(e) = entity {
+        if let Ok(bindings) = scopes.get(e) {
+            chain.push((Some(e), bindings));
+        }
+        entity = parents.get(e).ok().map(|child_of| child_of.parent());
+    }
+    if let Some(global) = global {
+        chain.push((None, &global.0));
+    }
+    chain
+}
+
+/// Fire the longest bound start of `keys` until none are left, dropping keys
+/// that start nothing bound.
+fn flush_chain<A: Clone + Send + Sync + 'static>(
+    chain: &[(Option<Entity>, &KeyBindings<A>)],
+    keys: &mut KeySeq<KeyCode>,
+    writer: &mut MessageWriter<KeyBindingAction<A>>,
+) {
+    while !keys.is_empty() {
+        let found = (1..=keys.len()).rev().find_map(|n| {
+            chain.iter().find_map(|(entity, bindings)| {
+                bindings
+                    .lookup(&keys[..n])
+                    .map(|action| (n, *entity, action))
+            })
+        });
+        match found {
+            Some((n, entity, action)) => {
+                writer.write(KeyBindingAction {
+                    action: action.clone(),
+                    entity,
+                });
+                keys.drain(..n);
+            }
+            None => {
+                keys.remove(0);
+            }
+        }
+    }
+}
+
+enum Resolution<'a, A> {
+    Fire(Option<Entity>, &'a A),
+    Wait,
+    None,
+}
+
+fn resolve_chain<'a, A: Send + Sync + 'static>(
+    chain: &[(Option<Entity>, &'a KeyBindings<A>)],
+    seq: &[(Modifiers, KeyCode)],
+) -> Resolution<'a, A> {
+    for (entity, bindings) in chain {
+        if bindings.is_prefix(seq) {
+            return Resolution::Wait;
+        }
+        if let Some(action) = bindings.lookup(seq) {
+            return Resolution::Fire(*entity, action);
+        }
+    }
+    Resolution::None
+}
+
+/// Shows the continuations of a pending key sequence, like Emacs's
+/// which-key. Add [WhichKeyPlugin] to use it.
+#[cfg(feature = "which-key")]
+#[derive(Resource, Clone, Debug)]
+pub struct WhichKey {
+    /// How long a sequence must be pending before the overlay appears.
+    pub delay: Duration,
+    /// How the chords are displayed.
+    pub style: Style,
+}
+
+#[cfg(feature = "which-key")]
+impl Default for WhichKey {
+    fn default() -> Self {
+        WhichKey {
+            delay: Duration::from_secs(1),
+            style: Style::native(),
+        }
+    }
+}
+
+/// Marks the overlay node spawned by [show_which_key].
+#[cfg(feature = "which-key")]
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct WhichKeyOverlay;
+
+/// Shows a [WhichKeyOverlay] listing the continuations of the
+/// [PendingKeys] of a [KeyBindingsPlugin] after the [WhichKey] delay.
+#[cfg(feature = "which-key")]
+pub struct WhichKeyPlugin<A>(PhantomData<A>);
+
+#[cfg(feature = "which-key")]
+impl<A> Default for WhichKeyPlugin<A> {
+    fn default() -> Self {
+        WhichKeyPlugin(PhantomData)
+    }
+}
+
+#[cfg(feature = "which-key")]
+impl<A: fmt::Display + Send + Sync + 'static> Plugin for WhichKeyPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WhichKey>()
+            .add_systems(Update, show_which_key::<A>);
+    }
+}
+
+/// The sequence shown by [show_which_key] and when it began.
+#[cfg(feature = "which-key")]
+#[derive(Default)]
+pub struct WhichKeyState {
+    keys: KeySeq<KeyCode>,
+    since: Duration,
+    shown: bool,
+}
+
+/// Spawn a [WhichKeyOverlay] once a sequence has been pending for the
+/// [WhichKey] delay and despawn it when the sequence ends. Each line shows a
+/// chord and its action or how many bindings continue past it.
+#[cfg(feature = "which-key")]
+#[allow(clippy::too_many_arguments)]
+pub fn show_which_key<A: fmt::Display + Send + Sync + 'static>(
+    time: Res<Time>,
+    config: Res<WhichKey>,
+    pending: Res<PendingKeys<A>>,
+    scopes: Query<&KeyBindings<A>>,
+    parents: Query<&ChildOf>,
+    global: Option<Res<GlobalKeyBindings<A>>>,
+    overlays: Query<Entity, With<WhichKeyOverlay>>,
+    mut state: Local<WhichKeyState>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed();
+    if state.keys != pending.keys {
+        for overlay in &overlays {
+            commands.entity(overlay).despawn();
+        }
+        state.keys.clone_from(&pending.keys);
+        state.since = now;
+        state.shown = false;
+    }
+    if state.keys.is_empty() || state.shown || now - state.since < config.delay {
+        return;
+    }
+    state.shown = true;
+    let chain = focus_chain(pending.focus, &scopes, &parents, global.as_deref());
+    // The first scope waiting on the sequence is the one that resolves it.
+    let Some((_, bindings)) = chain.iter().find(|(_, b)| b.is_prefix(&state.keys)) else {
+        return;
+    };
+    let lines: Vec<String> = bindings
+        .continuations(&state.keys)
+        .into_iter()
+        .map(|c| {
+            let chord = config.style.format_chord(c.chord.0, &key_name(&c.chord.1));
+            match (c.action, c.more) {
+                (Some(action), 0) => format!("{chord}  {action}"),
+                (Some(action), more) => format!("{chord}  {action} (+{more})"),
+                (None, more) => format!("{chord}  +{more}"),
+            }
+        })
+        .collect();
+    commands.spawn((
+        WhichKeyOverlay,
+        Text::new(lines.join("\n")),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+    ));
+}
+
+impl ChordCapture<KeyCode> {
+    /// Read the key presses and releases from `input` at time `now`, which is
+    /// usually `Time::elapsed()`.
+    ///
+    /// ```
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{Chord, Modifiers, capture::{Capture, ChordCapture}};
+    /// use std::time::Duration;
+    /// let mut capture = ChordCapture::new(KeyCode::Escape);
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// input.press(KeyCode::ControlLeft);
+    /// capture.read_input(&input, Duration::ZERO);
+    /// input.clear();
+    /// input.press(KeyCode::KeyS);
+    /// capture.read_input(&input, Duration::ZERO);
+    /// assert_eq!(capture.take(),
+    ///            Some(Capture::Chords(vec![Chord::new(Modifiers::CONTROL, KeyCode::KeyS)])));
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>, now: Duration) {
+        let mods = Modifiers::from(input);
+        for key in input.get_just_pressed() {
+            let modifier = Modifiers::from(*key);
+            if modifier.is_empty() {
+                self.press(mods, *key, now);
+            } else {
+                self.press_modifier(modifier);
+            }
+        }
+        for key in input.get_just_released() {
+            let modifier = Modifiers::from(*key);
+            if !modifier.is_empty() {
+                self.release_modifier(modifier, now);
+            }
+        }
+        self.update(now);
+    }
+}
+
+impl ModifierChordRecognizer {
+    /// Read the key presses and releases from `input`.
+    ///
+    /// ```
+    /// use bevy::input::{ButtonInput, keyboard::KeyCode};
+    /// use keyseq::{Modifiers, _keyseq, bevy::pchord, modifier_chord::ModifierChordRecognizer};
+    /// let mut recognizer = ModifierChordRecognizer::default();
+    /// let mut input = ButtonInput::<KeyCode>::default();
+    /// input.press(KeyCode::AltLeft);
+    /// recognizer.read_input(&input);
+    /// input.clear();
+    /// input.release(KeyCode::AltLeft);
+    /// recognizer.read_input(&input);
+    /// let switch_layout = pchord! { Ctrl-Shift };
+    /// for mods in recognizer.drain() {
+    ///     match mods {
+    ///         Modifiers::ALT => println!("Open the menu"),
+    ///         mods if switch_layout.matches_modifiers(mods) => println!("Switch layout"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn read_input(&mut self, input: &ButtonInput<KeyCode>) {
+        for key in input.get_just_pressed() {
+            let modifier = Modifiers::from(*key);
+            if modifier.is_empty() {
+                self.press_key();
+            } else {
+                self.press_modifier(modifier);
+            }
+        }
+        for key in input.get_just_released() {
+            self.release_modifier(Modifiers::from(*key));
+        }
+    }
+}
+
 /// Convenience wrapper to avoid `Modifier::from(&*input)` shenanigans due to
 /// resource type `Res<>` wrapper.
 impl From<&Res<'_, ButtonInput<KeyCode>>> for Modifiers {
@@ -75,6 +1100,44 @@ impl From<&Res<'_, ButtonInput<KeyCode>>> for Modifiers {
 /// assert_eq!(pkey! { Alt-1 },      (Modifiers::ALT, KeyCode::Digit1));
 /// ```
 ///
+/// A chord of only modifiers has no key, so it is a compile error. Use
+/// [pchord!] or [pchordseq!] instead, and see [ModifierChordRecognizer] to
+/// recognize them.
+///
+/// ```compile_fail
+/// use keyseq::{_keyseq, bevy::pkey};
+/// let _ = pkey! { Ctrl-Shift };
+/// ```
+///
+/// An optional modifier like `Shift?` expands to a match-or of each
+/// combination, so it only works in a pattern. In an expression it is a
+/// compile error; use [pchord!] to keep it at runtime.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, bevy::pkey};
+/// use bevy::input::keyboard::KeyCode;
+/// let zoom_in = |chord| matches!(chord, pkey! { Ctrl-Shift?-Equal });
+/// assert!(zoom_in((Modifiers::CONTROL, KeyCode::Equal)));
+/// assert!(zoom_in((Modifiers::CONTROL | Modifiers::SHIFT, KeyCode::Equal)));
+/// ```
+///
+/// ```compile_fail
+/// use keyseq::{_keyseq, bevy::pkey};
+/// let _ = pkey! { Ctrl-Shift?-Equal };
+/// ```
+///
+/// A range of digit, function, or numpad keys expands to a match-or pattern.
+/// See [key_index] to recover which key matched.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, bevy::pkey};
+/// use bevy::input::keyboard::KeyCode;
+/// assert!(matches!((Modifiers::ALT, KeyCode::Digit9), pkey! { Alt-(1..=9) }));
+/// assert!(matches!((Modifiers::NONE, KeyCode::F12),   pkey! { F(1..=12) }));
+/// assert!(matches!((Modifiers::NONE, KeyCode::Numpad0), pkey! { Numpad(0..10) }));
+/// assert!(!matches!((Modifiers::ALT, KeyCode::Digit0), pkey! { Alt-(1..=9) }));
+/// ```
+///
 /// More than one key will cause a panic at compile-time. Use keyseq! for that.
 ///
 /// ```compile_fail
@@ -132,3 +1195,82 @@ pub use keyseq_macros::bevy_lkey as lkey;
 ///
 /// [key]: https://docs.rs/bevy/latest/bevy/prelude/enum.Key.html
 pub use keyseq_macros::bevy_lkeyseq as lkeyseq;
+
+/// Short hand notation describes a physical key chord as
+/// [Chord](crate::Chord)`<`[bevy::input::keyboard::KeyCode][keycode]`>`.
+///
+/// Unlike [pkey], optional modifiers like `Shift?` are kept at runtime and
+/// treated as "don't care" flags by [Chord::matches](crate::Chord::matches).
+///
+/// ```
+/// use keyseq::{Chord, Modifiers, _keyseq, bevy::pchord};
+/// use bevy::input::keyboard::KeyCode;
+/// let zoom_in = pchord! { Ctrl-Shift?-Equal };
+/// assert_eq!(zoom_in, Chord::new(Modifiers::CONTROL, KeyCode::Equal)
+///                         .with_optional(Modifiers::SHIFT));
+/// assert!(zoom_in.matches(Modifiers::CONTROL, &KeyCode::Equal));
+/// assert!(zoom_in.matches(Modifiers::CONTROL | Modifiers::SHIFT, &KeyCode::Equal));
+/// ```
+///
+/// A chord may also be qualified by how it is pressed, see
+/// [Press](crate::Press), for use with a [PressRecognizer].
+///
+/// ```
+/// use std::time::Duration;
+/// use keyseq::{Chord, Modifiers, Press, _keyseq, bevy::pchord};
+/// use bevy::input::keyboard::KeyCode;
+/// assert_eq!(pchord! { Shift Shift },
+///            Chord::from_modifiers(Modifiers::SHIFT).with_press(Press::DoubleTap));
+/// assert_eq!(pchord! { DoubleTap(Shift) }, pchord! { Shift Shift });
+/// assert_eq!(pchord! { Tap(Ctrl) }, Chord::from_modifiers(Modifiers::CONTROL));
+/// assert_eq!(pchord! { Hold(Space) },
+///            Chord::new(Modifiers::NONE, KeyCode::Space).with_press(Press::Hold(Press::DEFAULT_HOLD)));
+/// assert_eq!(pchord! { Hold(Space, 750) }.press, Press::Hold(Duration::from_millis(750)));
+/// assert_eq!(pchord! { Release(Ctrl-A) },
+///            Chord::new(Modifiers::CONTROL, KeyCode::KeyA).with_press(Press::Release));
+/// ```
+///
+/// [keycode]: https://docs.rs/bevy/latest/bevy/prelude/enum.KeyCode.html
+pub use keyseq_macros::bevy_pchord as pchord;
+
+/// Short hand notation describes a sequence of physical key chords as
+/// `[`[Chord](crate::Chord)`<`[bevy::input::keyboard::KeyCode][keycode]`>]`.
+/// Unlike [pkeyseq!], an element may consist only of modifiers.
+///
+/// ```
+/// use keyseq::{Chord, Modifiers, _keyseq, bevy::pchordseq};
+/// use bevy::input::keyboard::KeyCode;
+/// assert_eq!(pchordseq! { Ctrl-Shift A },
+///            [Chord::from_modifiers(Modifiers::CONTROL | Modifiers::SHIFT),
+///             Chord::new(Modifiers::NONE, KeyCode::KeyA)]);
+/// ```
+///
+/// [keycode]: https://docs.rs/bevy/latest/bevy/prelude/enum.KeyCode.html
+pub use keyseq_macros::bevy_pchordseq as pchordseq;
+
+/// Short hand notation describes a logical key chord as
+/// [Chord](crate::Chord)`<`[bevy::input::keyboard::Key][key]`>`.
+///
+/// [key]: https://docs.rs/bevy/latest/bevy/prelude/enum.Key.html
+pub use keyseq_macros::bevy_lchord as lchord;
+
+/// Short hand notation describes a sequence of logical key chords as
+/// `[`[Chord](crate::Chord)`<`[bevy::input::keyboard::Key][key]`>]`.
+///
+/// [key]: https://docs.rs/bevy/latest/bevy/prelude/enum.Key.html
+pub use keyseq_macros::bevy_lchordseq as lchordseq;
+
+/// Short hand notation describes a physical key combo, several keys pressed at
+/// once, as `(modifiers: `[Modifiers]`, key_codes: [`[bevy::input::keyboard::KeyCode][keycode]`; N])`.
+///
+/// Use it with a [ComboDetector].
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, bevy::pcombo};
+/// use bevy::input::keyboard::KeyCode;
+/// assert_eq!(pcombo! { J+K }, (Modifiers::NONE, [KeyCode::KeyJ, KeyCode::KeyK]));
+/// assert_eq!(pcombo! { Ctrl-J+K }, (Modifiers::CONTROL, [KeyCode::KeyJ, KeyCode::KeyK]));
+/// ```
+///
+/// [keycode]: https://docs.rs/bevy/latest/bevy/prelude/enum.KeyCode.html
+pub use keyseq_macros::bevy_pcombo as pcombo;
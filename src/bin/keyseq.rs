@@ -0,0 +1,264 @@
+//! Validate, convert, and document binding files
+//!
+//! ```text
+//! keyseq check [--from NOTATION] FILE...
+//! keyseq convert --to NOTATION [--from NOTATION] [FILE...]
+//! keyseq cheatsheet [--from NOTATION] [--style STYLE] [--format FORMAT] [FILE...]
+//! ```
+//!
+//! A FILE of `-` or none reads standard input. `convert` writes each file in
+//! turn, and `cheatsheet` lists the bindings of every file. `check` also
+//! reports conflicts. A sequence that is a prefix of another is only a
+//! warning, since it fires when the longer one is broken off or times out. It
+//! exits with 1 if a file has errors or duplicate bindings and 2 on bad usage.
+use keyseq::{
+    cheatsheet::CheatSheet,
+    conflict::{find_conflicts, ConflictKind, MatchMode},
+    display::Style,
+    notation::{parse_bindings, FileBinding, Notation},
+};
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: keyseq check [--from NOTATION] FILE...
+       keyseq convert --to NOTATION [--from NOTATION] [FILE...]
+       keyseq cheatsheet [--from NOTATION] [--style STYLE] [--format FORMAT] [FILE...]
+
+NOTATION is keyseq (default), emacs, vim, vscode, or mac (write only).
+STYLE is keyseq (default), windows, mac, mac-words, gnome, emacs, or native.
+FORMAT is markdown (default), html, or json.";
+
+struct Options {
+    from: Notation,
+    to: Option<Notation>,
+    style: Style,
+    format: String,
+    files: Vec<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        from: Notation::Keyseq,
+        to: None,
+        style: Style::Keyseq,
+        format: "markdown".to_string(),
+        files: vec![],
+    };
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+        match arg.as_str() {
+            "--from" => {
+                let name = value("--from")?;
+                options.from =
+                    Notation::from_name(&name).ok_or(format!("unknown notation `{name}`"))?;
+            }
+            "--to" => {
+                let name = value("--to")?;
+                options.to =
+                    Some(Notation::from_name(&name).ok_or(format!("unknown notation `{name}`"))?);
+            }
+            "--style" => {
+                let name = value("--style")?;
+                options.style = match name.as_str() {
+                    "keyseq" => Style::Keyseq,
+                    "windows" => Style::Windows,
+                    "mac" => Style::MacGlyphs,
+                    "mac-words" => Style::MacWords,
+                    "gnome" => Style::Gnome,
+                    "emacs" => Style::Emacs,
+                    "native" => Style::native(),
+                    _ => return Err(format!("unknown style `{name}`")),
+                };
+            }
+            "--format" => {
+                options.format = value("--format")?;
+                if !["markdown", "html", "json"].contains(&options.format.as_str()) {
+                    return Err(format!("unknown format `{}`", options.format));
+                }
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option `{arg}`")),
+            _ => options.files.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn read_file(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Read the bindings of `path`, reporting errors on standard error.
+fn load(path: &str, from: Notation) -> Option<(String, Vec<FileBinding>)> {
+    let text = match read_file(path) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("{path}: {error}");
+            return None;
+        }
+    };
+    match parse_bindings(&text, from) {
+        Ok(bindings) => Some((text, bindings)),
+        Err(errors) => {
+            for (line, error) in errors {
+                eprintln!("{path}:{line}: {error}");
+            }
+            None
+        }
+    }
+}
+
+/// Report the conflicts among `bindings` on standard error and return true if
+/// any is an error rather than a warning.
+fn report_conflicts(path: &str, from: Notation, bindings: &[FileBinding]) -> bool {
+    let conflicts = find_conflicts(
+        bindings.iter().map(|binding| binding.keys.as_slice()),
+        MatchMode::Exact,
+    );
+    let mut failed = false;
+    for conflict in &conflicts {
+        let first = &bindings[conflict.first];
+        let second = &bindings[conflict.second];
+        let (level, kind) = match conflict.kind {
+            ConflictKind::Duplicate => ("error", "same keys"),
+            ConflictKind::Prefix => ("warning", "one is a prefix of the other"),
+            ConflictKind::Superset => {
+                ("error", "both match when their modifiers are held together")
+            }
+        };
+        failed |= level == "error";
+        eprintln!(
+            "{path}:{}: {level}: `{}` conflicts with `{}` on line {}: {kind}",
+            second.line,
+            from.format(&second.keys),
+            from.format(&first.keys),
+            first.line
+        );
+    }
+    failed
+}
+
+/// Return the files to read, standard input if none.
+fn paths(options: &Options) -> Vec<&str> {
+    if options.files.is_empty() {
+        vec!["-"]
+    } else {
+        options.files.iter().map(String::as_str).collect()
+    }
+}
+
+fn check(options: &Options) -> ExitCode {
+    if options.files.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    }
+    let mut ok = true;
+    for path in &options.files {
+        match load(path, options.from) {
+            Some((_, bindings)) if !report_conflicts(path, options.from, &bindings) => {
+                println!("{path}: {} bindings ok", bindings.len())
+            }
+            _ => ok = false,
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn convert(options: &Options) -> ExitCode {
+    let Some(to) = options.to else {
+        eprintln!("convert needs --to\n{USAGE}");
+        return ExitCode::from(2);
+    };
+    let mut ok = true;
+    for path in paths(options) {
+        let Some((text, bindings)) = load(path, options.from) else {
+            ok = false;
+            continue;
+        };
+        let mut bindings = bindings.into_iter().peekable();
+        // Keep comments and groups; rewrite only the binding lines. A line
+        // with optional modifiers becomes a line for each combination.
+        for (index, line) in text.lines().enumerate() {
+            let mut rewritten = false;
+            while let Some(binding) = bindings.next_if(|binding| binding.line == index + 1) {
+                println!("{} = {}", to.format(&binding.keys), binding.action);
+                rewritten = true;
+            }
+            if !rewritten {
+                println!("{line}");
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn cheatsheet(options: &Options) -> ExitCode {
+    let paths = paths(options);
+    let mut sheet = CheatSheet::new(paths.join(", "));
+    let mut ok = true;
+    for path in paths {
+        let Some((_, bindings)) = load(path, options.from) else {
+            ok = false;
+            continue;
+        };
+        let mut bindings = bindings.into_iter().peekable();
+        while let Some(binding) = bindings.next() {
+            // The bindings of one line share an entry.
+            let mut keys = vec![binding.keys];
+            while let Some(next) = bindings.next_if(|next| next.line == binding.line) {
+                keys.push(next.keys);
+            }
+            sheet.add(&binding.group, &binding.action, "", keys);
+        }
+    }
+    if !ok {
+        return ExitCode::FAILURE;
+    }
+    let output = match options.format.as_str() {
+        "html" => sheet.html(options.style),
+        "json" => sheet.json(options.style) + "\n",
+        _ => sheet.markdown(options.style),
+    };
+    print!("{output}");
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("keyseq: {message}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match command.as_deref() {
+        Some("check") => check(&options),
+        Some("convert") => convert(&options),
+        Some("cheatsheet") => cheatsheet(&options),
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
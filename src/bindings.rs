@@ -0,0 +1,238 @@
+//! A static table of key bindings for an action enum
+//!
+//! The `keymap!` macro generates an action enum and implements [KeyBindings]
+//! for it, so the actions, the match on keys, and the help text come from one
+//! place.
+//!
+//! ```
+//! use keyseq::{Modifiers, _keyseq, keymap, bindings::KeyBindings};
+//!
+//! keymap! {
+//!     pub EditorKeys for poor {
+//!         /// Save the file
+//!         Ctrl-X Ctrl-S => Save,
+//!         Ctrl-Z => Undo,
+//!         Super-Z => Undo,
+//!     }
+//! }
+//!
+//! assert_eq!(EditorKeys::lookup_chord(Modifiers::CONTROL, &"Z"), Some(EditorKeys::Undo));
+//! let history = [(Modifiers::NONE, "A"), (Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "S")];
+//! assert_eq!(EditorKeys::lookup_suffix(&history), Some((EditorKeys::Save, 2)));
+//! assert!(EditorKeys::is_prefix(&[(Modifiers::CONTROL, "X")]));
+//! assert_eq!(EditorKeys::Save.description(), "Save the file");
+//! assert_eq!(EditorKeys::Undo.to_string(), "Undo (Ctrl-Z, Super-Z)");
+//! assert_eq!(EditorKeys::listing().to_string(),
+//!            "Ctrl-X Ctrl-S  Save\nCtrl-Z         Undo\nSuper-Z        Undo\n");
+//! ```
+//!
+//! Ambiguous bindings are compile errors, see [conflict](crate::conflict).
+//!
+//! ```compile_fail
+//! use keyseq::{_keyseq, keymap};
+//! keymap! {
+//!     Keys for poor {
+//!         Ctrl-X => Cut,
+//!         Ctrl-X Ctrl-S => Save, // error: `Ctrl-X` shadows `Ctrl-X Ctrl-S`
+//!     }
+//! }
+//! ```
+//!
+//! With superset matching, held modifiers only need to include the bound ones
+//! and the binding with the most modifiers wins.
+//!
+//! ```
+//! use keyseq::{Modifiers, _keyseq, keymap, bindings::KeyBindings};
+//! keymap! {
+//!     Keys for poor, superset {
+//!         Ctrl-S => Save,
+//!         Ctrl-Shift-S => SaveAs,
+//!         Ctrl-Alt-A => SelectAll,
+//!     }
+//! }
+//! assert_eq!(Keys::lookup_chord(Modifiers::CONTROL | Modifiers::ALT, &"S"), Some(Keys::Save));
+//! assert_eq!(Keys::lookup_chord(Modifiers::CONTROL | Modifiers::SHIFT, &"S"), Some(Keys::SaveAs));
+//! assert_eq!(Keys::lookup_chord(Modifiers::ALT, &"A"), None);
+//! assert!(Keys::conflicts().is_empty());
+//! ```
+//!
+//! Bindings that neither wins are still errors.
+//!
+//! ```compile_fail
+//! use keyseq::{_keyseq, keymap};
+//! keymap! {
+//!     Keys for poor, superset {
+//!         Ctrl-S => Save,
+//!         Alt-S => Sort, // error: both match with Ctrl and Alt held
+//!     }
+//! }
+//! ```
+use super::{
+    conflict::{find_conflicts, Conflict, MatchMode},
+    Modifiers,
+};
+use std::fmt;
+use std::marker::PhantomData;
+
+#[cfg(any(feature = "poor", feature = "bevy", feature = "winit"))]
+pub use keyseq_macros::KeyBindings;
+
+/// A key sequence bound to an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Binding<K: 'static, A> {
+    /// The chords of the key sequence.
+    pub keys: &'static [(Modifiers, K)],
+    /// The key sequence as written, e.g., "Ctrl-X Ctrl-S".
+    pub text: &'static str,
+    /// The action it triggers.
+    pub action: A,
+}
+
+/// An action enum with a static table of key bindings.
+///
+/// Implement it with the `keymap!` macro or derive it.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, bindings::KeyBindings};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, KeyBindings)]
+/// #[key_bindings(poor)]
+/// enum Action {
+///     /// Save the file
+///     #[keys(Ctrl-S)]
+///     Save,
+///     #[keys(Ctrl-X Ctrl-C, Alt-F4)]
+///     Quit,
+///     Jump,
+/// }
+///
+/// assert_eq!(Action::lookup_chord(Modifiers::ALT, &"F4"), Some(Action::Quit));
+/// assert_eq!(Action::default_bindings().len(), 3);
+/// assert_eq!(Action::Quit.default_keys().count(), 2);
+/// assert_eq!(Action::Quit.to_string(), "Quit (Ctrl-X Ctrl-C, Alt-F4)");
+/// assert_eq!(Action::Jump.to_string(), "Jump");
+/// assert_eq!(Action::Save.description(), "Save the file");
+/// assert_eq!(Action::Jump.description(), "");
+/// ```
+pub trait KeyBindings: Sized + Copy + PartialEq + 'static {
+    /// The key type, e.g., `KeyCode`.
+    type Key: PartialEq + 'static;
+
+    /// Return the bindings in the order they were declared.
+    fn bindings() -> &'static [Binding<Self::Key, Self>];
+
+    /// Return the name of the action.
+    fn name(&self) -> &'static str;
+
+    /// Return what the action does, i.e., the doc comment of its variant or
+    /// of its first binding in `keymap!`, or "" if there is none.
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    /// Return the bindings of this action.
+    fn bindings_of(self) -> impl Iterator<Item = &'static Binding<Self::Key, Self>> {
+        Self::bindings()
+            .iter()
+            .filter(move |binding| binding.action == self)
+    }
+
+    /// How held modifiers are matched. With [MatchMode::Superset] the binding
+    /// with the most modifiers wins.
+    const MATCH_MODE: MatchMode = MatchMode::Exact;
+
+    /// Return the action bound to the key sequence `seq`.
+    fn lookup(seq: &[(Modifiers, Self::Key)]) -> Option<Self> {
+        Self::bindings()
+            .iter()
+            .rev()
+            .filter(|binding| Self::MATCH_MODE.matches_seq(binding.keys, seq))
+            .max_by_key(|binding| modifier_count(binding.keys))
+            .map(|binding| binding.action)
+    }
+
+    /// Return the action bound to the single chord `mods` and `key`.
+    fn lookup_chord(mods: Modifiers, key: &Self::Key) -> Option<Self> {
+        Self::bindings()
+            .iter()
+            .rev()
+            .filter(|binding| {
+                matches!(binding.keys, [(m, k)] if k == key && Self::MATCH_MODE.matches(*m, mods))
+            })
+            .max_by_key(|binding| modifier_count(binding.keys))
+            .map(|binding| binding.action)
+    }
+
+    /// Return the action whose key sequence ends `history` and its length. The
+    /// longest such sequence wins.
+    fn lookup_suffix(history: &[(Modifiers, Self::Key)]) -> Option<(Self, usize)> {
+        Self::bindings()
+            .iter()
+            .rev()
+            .filter(|binding| {
+                let n = binding.keys.len();
+                n <= history.len()
+                    && Self::MATCH_MODE.matches_seq(binding.keys, &history[history.len() - n..])
+            })
+            .max_by_key(|binding| (binding.keys.len(), modifier_count(binding.keys)))
+            .map(|binding| (binding.action, binding.keys.len()))
+    }
+
+    /// Return true if `seq` is the start of a longer bound key sequence.
+    fn is_prefix(seq: &[(Modifiers, Self::Key)]) -> bool {
+        Self::bindings().iter().any(|binding| {
+            binding.keys.len() > seq.len()
+                && Self::MATCH_MODE.matches_seq(&binding.keys[..seq.len()], seq)
+        })
+    }
+
+    /// Return the ambiguous bindings. The indices refer to
+    /// [bindings()](KeyBindings::bindings).
+    fn conflicts() -> Vec<Conflict> {
+        find_conflicts(
+            Self::bindings().iter().map(|binding| binding.keys),
+            Self::MATCH_MODE,
+        )
+    }
+
+    /// Return a listing of the bindings that implements `Display`.
+    fn listing() -> Listing<Self> {
+        Listing(PhantomData)
+    }
+}
+
+/// Count the modifiers of a key sequence. Lookups iterate in reverse so that
+/// `max_by_key()` prefers the first declared binding among equals.
+pub(crate) fn modifier_count<K>(keys: &[(Modifiers, K)]) -> u32 {
+    keys.iter().map(|(m, _)| m.bits().count_ones()).sum()
+}
+
+/// Lists the key bindings of `A` one per line with the actions aligned.
+pub struct Listing<A>(PhantomData<A>);
+
+impl<A: KeyBindings> fmt::Display for Listing<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = A::bindings()
+            .iter()
+            .map(|binding| binding.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        for binding in A::bindings() {
+            writeln!(f, "{:width$}  {}", binding.text, binding.action.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the name of `action` followed by its shortcuts, e.g., "Undo (Ctrl-Z)".
+pub fn fmt_action<A: KeyBindings>(action: &A, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(action.name())?;
+    for (i, binding) in action.bindings_of().enumerate() {
+        f.write_str(if i == 0 { " (" } else { ", " })?;
+        f.write_str(binding.text)?;
+    }
+    if action.bindings_of().next().is_some() {
+        f.write_str(")")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Capture the next chord or key sequence, e.g., for a rebinding screen
+//!
+//! A "press a key to rebind" screen needs the next complete chord, not the
+//! modifier keys on their way down. A [ChordCapture] tracks the modifiers,
+//! ignores lone modifier presses unless modifier-only chords are allowed, and
+//! cancels on its cancel key, usually Escape.
+//!
+//! ```
+//! use std::time::Duration;
+//! use keyseq::{Chord, Modifiers, capture::{Capture, ChordCapture}};
+//!
+//! let ms = Duration::from_millis;
+//! let mut capture = ChordCapture::new("Escape");
+//! capture.press_modifier(Modifiers::CONTROL);
+//! assert!(!capture.is_done());
+//! capture.press(Modifiers::CONTROL, "S", ms(0));
+//! assert_eq!(capture.take(), Some(Capture::Chords(vec![Chord::new(Modifiers::CONTROL, "S")])));
+//!
+//! // Capture a sequence of up to two chords.
+//! let mut capture = ChordCapture::new("Escape").sequence(2, ms(1000));
+//! capture.press(Modifiers::NONE, "G", ms(0));
+//! capture.update(ms(500));
+//! assert!(!capture.is_done());
+//! capture.press(Modifiers::NONE, "Escape", ms(600));
+//! assert_eq!(capture.take(), Some(Capture::Cancelled));
+//! ```
+use super::{modifier_chord::ModifierChordRecognizer, Chord, Modifiers};
+use std::time::Duration;
+
+/// The outcome of a [ChordCapture].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Capture<K> {
+    /// The captured chords, one unless capturing a sequence.
+    Chords(Vec<Chord<K>>),
+    /// The cancel key was pressed.
+    Cancelled,
+}
+
+/// Waits for the next complete chord or key sequence.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct ChordCapture<K> {
+    cancel: K,
+    max_len: usize,
+    /// The pause that ends a sequence early.
+    timeout: Option<Duration>,
+    allow_modifier_only: bool,
+    modifiers: ModifierChordRecognizer,
+    chords: Vec<Chord<K>>,
+    last_press: Option<Duration>,
+    result: Option<Capture<K>>,
+}
+
+impl<K: Clone + PartialEq> ChordCapture<K> {
+    /// Capture one chord. Pressing `cancel` without modifiers cancels.
+    pub fn new(cancel: K) -> Self {
+        ChordCapture {
+            cancel,
+            max_len: 1,
+            timeout: None,
+            allow_modifier_only: false,
+            modifiers: ModifierChordRecognizer::default(),
+            chords: vec![],
+            last_press: None,
+            result: None,
+        }
+    }
+
+    /// Capture a sequence of up to `max_len` chords. It ends early when no
+    /// chord is pressed for `timeout`.
+    pub fn sequence(mut self, max_len: usize, timeout: Duration) -> Self {
+        self.max_len = max_len.max(1);
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Capture chords of only modifiers, e.g., `Ctrl-Shift`, if `allow` is
+    /// true. They complete when the modifiers are released.
+    pub fn allow_modifier_only(mut self, allow: bool) -> Self {
+        self.allow_modifier_only = allow;
+        self
+    }
+
+    /// Record a modifier key press.
+    pub fn press_modifier(&mut self, modifier: Modifiers) {
+        self.modifiers.press_modifier(modifier);
+    }
+
+    /// Record a modifier key release at time `now`.
+    pub fn release_modifier(&mut self, modifier: Modifiers, now: Duration) {
+        self.modifiers.release_modifier(modifier);
+        let fired: Vec<Modifiers> = self.modifiers.drain().collect();
+        if self.allow_modifier_only {
+            for mods in fired {
+                self.push(Chord::from_modifiers(mods), now);
+            }
+        }
+    }
+
+    /// Record a non-modifier key press with the modifiers held at time `now`.
+    pub fn press(&mut self, mods: Modifiers, key: K, now: Duration) {
+        self.modifiers.press_key();
+        if self.result.is_some() {
+            return;
+        }
+        if mods.is_empty() && key == self.cancel {
+            self.chords.clear();
+            self.result = Some(Capture::Cancelled);
+            return;
+        }
+        self.push(Chord::new(mods, key), now);
+    }
+
+    fn push(&mut self, chord: Chord<K>, now: Duration) {
+        if self.result.is_some() {
+            return;
+        }
+        self.chords.push(chord);
+        self.last_press = Some(now);
+        if self.chords.len() >= self.max_len {
+            self.finish();
+        }
+    }
+
+    /// Advance the clock to `now`, which ends a sequence after a pause.
+    pub fn update(&mut self, now: Duration) {
+        if let (Some(timeout), Some(last)) = (self.timeout, self.last_press) {
+            if self.result.is_none() && now.saturating_sub(last) >= timeout {
+                self.finish();
+            }
+        }
+    }
+
+    /// End the capture with the chords pressed so far, if any.
+    pub fn finish(&mut self) {
+        if self.result.is_none() && !self.chords.is_empty() {
+            self.result = Some(Capture::Chords(std::mem::take(&mut self.chords)));
+        }
+    }
+
+    /// Return the chords of a sequence in progress.
+    pub fn chords(&self) -> &[Chord<K>] {
+        &self.chords
+    }
+
+    /// Return true if the capture completed or was cancelled.
+    pub fn is_done(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Take the outcome, which readies the capture for another.
+    pub fn take(&mut self) -> Option<Capture<K>> {
+        let result = self.result.take();
+        if result.is_some() {
+            self.last_press = None;
+        }
+        result
+    }
+
+    /// Drop any chords in progress and any outcome.
+    pub fn reset(&mut self) {
+        self.chords.clear();
+        self.last_press = None;
+        self.result = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lone_modifiers_ignored_unless_allowed() {
+        let now = Duration::ZERO;
+        let mut capture = ChordCapture::new("Escape");
+        capture.press_modifier(Modifiers::ALT);
+        capture.release_modifier(Modifiers::ALT, now);
+        assert!(!capture.is_done());
+
+        let mut capture = ChordCapture::new("Escape").allow_modifier_only(true);
+        capture.press_modifier(Modifiers::CONTROL);
+        capture.press_modifier(Modifiers::SHIFT);
+        capture.release_modifier(Modifiers::SHIFT, now);
+        capture.release_modifier(Modifiers::CONTROL, now);
+        assert_eq!(
+            capture.take(),
+            Some(Capture::Chords(vec![Chord::from_modifiers(
+                Modifiers::CONTROL | Modifiers::SHIFT
+            )]))
+        );
+    }
+
+    #[test]
+    fn sequence_ends_on_timeout_or_length() {
+        let ms = Duration::from_millis;
+        let mut capture = ChordCapture::new("Escape").sequence(2, ms(1000));
+        capture.press(Modifiers::NONE, "G", ms(0));
+        capture.update(ms(1000));
+        assert_eq!(
+            capture.take(),
+            Some(Capture::Chords(vec![Chord::new(Modifiers::NONE, "G")]))
+        );
+        capture.press(Modifiers::CONTROL, "X", ms(2000));
+        capture.press(Modifiers::CONTROL, "S", ms(2100));
+        assert_eq!(
+            capture
+                .take()
+                .map(|c| matches!(c, Capture::Chords(v) if v.len() == 2)),
+            Some(true)
+        );
+        // Shift-Escape is a chord, not a cancel.
+        capture.press(Modifiers::SHIFT, "Escape", ms(3000));
+        assert!(!capture.is_done());
+    }
+}
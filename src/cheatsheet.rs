@@ -0,0 +1,364 @@
+//! Generate shortcut reference docs from key bindings
+//!
+//! Hand-written shortcut references go out of date. A [CheatSheet] collects
+//! actions, their descriptions, and their key sequences in groups, then renders
+//! them as a Markdown table, a standalone HTML page, or JSON. Each rendering
+//! takes a [Style] so the same sheet can be produced per platform.
+//!
+//! ```
+//! use keyseq::{cheatsheet::CheatSheet, display::Style, notation::parse_keyseq};
+//!
+//! let mut sheet = CheatSheet::new("Editor");
+//! sheet.add("File", "Save", "Save the file", [parse_keyseq("Ctrl-S").unwrap()]);
+//! sheet.add("File", "Save", "", [parse_keyseq("Ctrl-X Ctrl-S").unwrap()]);
+//! sheet.add("Edit", "Undo", "Undo the last change", [parse_keyseq("Ctrl-Z").unwrap()]);
+//!
+//! let markdown = sheet.markdown(Style::Windows);
+//! assert_eq!(markdown.lines().take(5).collect::<Vec<_>>(), [
+//!     "## File",
+//!     "",
+//!     "| Action | Keys | Description |",
+//!     "| --- | --- | --- |",
+//!     "| Save | `Ctrl+S`, `Ctrl+X, Ctrl+S` | Save the file |",
+//! ]);
+//! assert!(markdown.contains("## Edit"));
+//! assert!(sheet.html(Style::MacGlyphs).contains("<td>Undo</td><td><kbd>⌃Z</kbd></td>"));
+//! assert!(sheet.json(Style::Keyseq).starts_with(r#"{"title":"Editor","groups":[{"name":"File""#));
+//! ```
+use super::{
+    bindings::KeyBindings,
+    display::Style,
+    keymap::{KeySeq, Keymap},
+    notation, Modifiers,
+};
+use std::fmt::{self, Write};
+
+/// An action and the key sequences bound to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Entry {
+    /// The name of the action.
+    pub action: String,
+    /// What the action does.
+    pub description: String,
+    /// The key sequences in keyseq's notation, see [notation].
+    pub keys: Vec<KeySeq<String>>,
+}
+
+/// A named group of entries.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Group {
+    /// The name of the group, e.g., "File".
+    pub name: String,
+    /// The entries in the order they were added.
+    pub entries: Vec<Entry>,
+}
+
+/// A shortcut reference in groups.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CheatSheet {
+    /// The title of the HTML page and the JSON.
+    pub title: String,
+    /// The groups in the order they were added.
+    pub groups: Vec<Group>,
+}
+
+impl CheatSheet {
+    /// Create an empty cheat sheet.
+    pub fn new(title: impl Into<String>) -> Self {
+        CheatSheet {
+            title: title.into(),
+            groups: vec![],
+        }
+    }
+
+    /// Add `keys` to `action` in `group`. An action added again keeps its
+    /// first non-empty description.
+    pub fn add(
+        &mut self,
+        group: &str,
+        action: &str,
+        description: &str,
+        keys: impl IntoIterator<Item = KeySeq<String>>,
+    ) {
+        let group = match self.groups.iter().position(|g| g.name == group) {
+            Some(index) => &mut self.groups[index],
+            None => {
+                self.groups.push(Group {
+                    name: group.to_string(),
+                    entries: vec![],
+                });
+                self.groups.last_mut().unwrap()
+            }
+        };
+        let entry = match group.entries.iter().position(|e| e.action == action) {
+            Some(index) => &mut group.entries[index],
+            None => {
+                group.entries.push(Entry {
+                    action: action.to_string(),
+                    ..Entry::default()
+                });
+                group.entries.last_mut().unwrap()
+            }
+        };
+        if entry.description.is_empty() {
+            entry.description = description.to_string();
+        }
+        entry.keys.extend(keys);
+    }
+
+    /// Set the description of every entry for `action`.
+    pub fn describe(&mut self, action: &str, description: &str) {
+        for entry in self.groups.iter_mut().flat_map(|g| g.entries.iter_mut()) {
+            if entry.action == action {
+                entry.description = description.to_string();
+            }
+        }
+    }
+
+    /// Add the bindings of `B` to `group` with the descriptions of their
+    /// actions. Keys keyseq's notation cannot read, e.g., the physical key
+    /// `Semicolon`, are shown as written.
+    pub fn add_bindings<B: KeyBindings>(&mut self, group: &str) {
+        for binding in B::bindings() {
+            let keys = notation::parse_keyseq(binding.text)
+                .unwrap_or_else(|_| vec![(Modifiers::NONE, binding.text.to_string())]);
+            self.add(
+                group,
+                binding.action.name(),
+                binding.action.description(),
+                [keys],
+            );
+        }
+    }
+
+    /// Add the effective bindings of `keymap` to `group`.
+    pub fn add_keymap<K, A>(&mut self, group: &str, keymap: &Keymap<K, A>)
+    where
+        K: AsRef<str> + Clone + PartialEq,
+        A: fmt::Display + Clone + PartialEq,
+    {
+        for (keys, action) in keymap.effective() {
+            let keys: KeySeq<String> = keys
+                .iter()
+                .map(|(mods, key)| (*mods, key.as_ref().to_string()))
+                .collect();
+            self.add(group, &action.to_string(), "", [keys]);
+        }
+    }
+
+    fn keys_of(entry: &Entry, style: Style) -> impl Iterator<Item = String> + '_ {
+        entry.keys.iter().map(move |keys| style.format_keyseq(keys))
+    }
+
+    /// Render a Markdown table per group.
+    pub fn markdown(&self, style: Style) -> String {
+        let mut out = String::new();
+        for (i, group) in self.groups.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "## {}\n", group.name);
+            out.push_str("| Action | Keys | Description |\n| --- | --- | --- |\n");
+            for entry in &group.entries {
+                let keys: Vec<String> = Self::keys_of(entry, style)
+                    .map(|keys| markdown_code(&keys))
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    markdown_cell(&entry.action),
+                    keys.join(", "),
+                    markdown_cell(&entry.description)
+                );
+            }
+        }
+        out
+    }
+
+    /// Render a standalone HTML page.
+    pub fn html(&self, style: Style) -> String {
+        let title = html_escape(&self.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; }}\n\
+             kbd {{ border: 1px solid #aaa; border-radius: 3px; padding: 0 0.25em; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for group in &self.groups {
+            let _ = writeln!(out, "<h2>{}</h2>", html_escape(&group.name));
+            out.push_str("<table>\n<tr><th>Action</th><th>Keys</th><th>Description</th></tr>\n");
+            for entry in &group.entries {
+                let keys: Vec<String> = Self::keys_of(entry, style)
+                    .map(|keys| format!("<kbd>{}</kbd>", html_escape(&keys)))
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    html_escape(&entry.action),
+                    keys.join(", "),
+                    html_escape(&entry.description)
+                );
+            }
+            out.push_str("</table>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Render JSON. Each entry has its keys as displayed in `style` and in
+    /// keyseq's notation.
+    pub fn json(&self, style: Style) -> String {
+        let strings = |items: &mut dyn Iterator<Item = String>| {
+            let items: Vec<String> = items.map(|s| json_string(&s)).collect();
+            format!("[{}]", items.join(","))
+        };
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let entries: Vec<String> = group
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            r#"{{"action":{},"description":{},"keys":{},"notation":{}}}"#,
+                            json_string(&entry.action),
+                            json_string(&entry.description),
+                            strings(&mut Self::keys_of(entry, style)),
+                            strings(
+                                &mut entry.keys.iter().map(|keys| notation::format_keyseq(keys))
+                            )
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#"{{"name":{},"entries":[{}]}}"#,
+                    json_string(&group.name),
+                    entries.join(",")
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"title":{},"groups":[{}]}}"#,
+            json_string(&self.title),
+            groups.join(",")
+        )
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn markdown_code(text: &str) -> String {
+    // A backtick in the code needs a longer fence and padding.
+    if text.contains('`') {
+        format!("`` {} ``", markdown_cell(text))
+    } else {
+        format!("`{}`", markdown_cell(text))
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet() -> CheatSheet {
+        let mut sheet = CheatSheet::new("Tom & Jerry");
+        sheet.add(
+            "Misc",
+            "Pipe",
+            "a | b",
+            [vec![(Modifiers::SHIFT, "\\".to_string())]],
+        );
+        sheet.add(
+            "Misc",
+            "Tick",
+            "",
+            [vec![(Modifiers::NONE, "`".to_string())]],
+        );
+        sheet
+    }
+
+    #[test]
+    fn escapes() {
+        let sheet = sheet();
+        let markdown = sheet.markdown(Style::Keyseq);
+        assert!(markdown.contains("| Pipe | `Shift-\\` | a \\| b |"));
+        assert!(markdown.contains("| Tick | `` ` `` |  |"));
+        assert!(sheet
+            .html(Style::Keyseq)
+            .contains("<title>Tom &amp; Jerry</title>"));
+        assert_eq!(
+            sheet.json(Style::Gnome),
+            r#"{"title":"Tom & Jerry","groups":[{"name":"Misc","entries":[{"action":"Pipe","description":"a | b","keys":["Shift+\\"],"notation":["Shift-\\"]},{"action":"Tick","description":"","keys":["`"],"notation":["`"]}]}]}"#
+        );
+    }
+
+    #[test]
+    fn keymaps_and_descriptions() {
+        let mut keymap = Keymap::new();
+        let layer = keymap.add_layer("defaults");
+        keymap.bind(layer, [(Modifiers::CONTROL, "S".to_string())], "Save");
+        keymap.bind(layer, [(Modifiers::SUPER, "S".to_string())], "Save");
+        let mut sheet = CheatSheet::new("Keys");
+        sheet.add_keymap("File", &keymap);
+        sheet.describe("Save", "Save the file");
+        let entry = &sheet.groups[0].entries[0];
+        assert_eq!(entry.keys.len(), 2);
+        assert_eq!(entry.description, "Save the file");
+    }
+
+    #[cfg(feature = "poor")]
+    #[test]
+    fn key_bindings() {
+        use crate::{_keyseq, keymap};
+        keymap! {
+            Keys for poor {
+                /// Save the file
+                Ctrl-S => Save,
+                Ctrl-Semicolon => Comment,
+                Super-S => Save,
+            }
+        }
+        let mut sheet = CheatSheet::new("Keys");
+        sheet.add_bindings::<Keys>("Edit");
+        let markdown = sheet.markdown(Style::Keyseq);
+        assert!(markdown.contains("| Save | `Ctrl-S`, `Super-S` | Save the file |"));
+        assert!(markdown.contains("| Comment | `Ctrl-Semicolon` |  |"));
+    }
+}
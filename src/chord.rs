@@ -0,0 +1,126 @@
+//! A runtime key chord
+use super::Modifiers;
+use std::time::Duration;
+
+/// How a chord is pressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Press {
+    /// The chord is pressed. This is the usual kind.
+    #[default]
+    Press,
+    /// The chord is pressed and released quickly with no other key in between.
+    Tap,
+    /// The chord is tapped twice in quick succession.
+    DoubleTap,
+    /// The chord is held for at least this long.
+    Hold(Duration),
+    /// The chord is released.
+    Release,
+}
+
+impl Press {
+    /// The duration of `Hold(Space)` when none is given.
+    pub const DEFAULT_HOLD: Duration = Duration::from_millis(500);
+}
+
+/// A key chord that may treat some modifiers as "don't care" flags.
+///
+/// The tuple returned by `pkey!` can only describe modifiers that must be held
+/// exactly. A chord can also carry optional modifiers, which are ignored
+/// when matching.
+///
+/// ```
+/// use keyseq::{Chord, Modifiers};
+/// let zoom = Chord::new(Modifiers::CONTROL, "Equal").with_optional(Modifiers::SHIFT);
+/// assert!(zoom.matches(Modifiers::CONTROL, &"Equal"));
+/// assert!(zoom.matches(Modifiers::CONTROL | Modifiers::SHIFT, &"Equal"));
+/// assert!(!zoom.matches(Modifiers::NONE, &"Equal"));
+/// assert!(!zoom.matches(Modifiers::CONTROL | Modifiers::ALT, &"Equal"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Chord<K> {
+    /// Modifiers that must be held.
+    pub mods: Modifiers,
+    /// Modifiers that may or may not be held.
+    pub optional: Modifiers,
+    /// The key or `None` if the chord consists only of modifiers.
+    pub key: Option<K>,
+    /// How the chord is pressed.
+    pub press: Press,
+}
+
+impl<K> Chord<K> {
+    /// Create a chord with no optional modifiers.
+    pub fn new(mods: Modifiers, key: K) -> Self {
+        Chord {
+            mods,
+            optional: Modifiers::NONE,
+            key: Some(key),
+            press: Press::Press,
+        }
+    }
+
+    /// Create a chord that consists only of modifiers, e.g., tapping Ctrl.
+    pub fn from_modifiers(mods: Modifiers) -> Self {
+        Chord {
+            mods,
+            optional: Modifiers::NONE,
+            key: None,
+            press: Press::Tap,
+        }
+    }
+
+    /// Set how the chord is pressed.
+    pub fn with_press(mut self, press: Press) -> Self {
+        self.press = press;
+        self
+    }
+
+    /// Treat the given modifiers as "don't care" flags.
+    pub fn with_optional(mut self, optional: Modifiers) -> Self {
+        self.optional = optional;
+        self
+    }
+
+    /// Return true if `mods` satisfy the required and optional modifiers.
+    pub fn matches_modifiers(&self, mods: Modifiers) -> bool {
+        mods.difference(self.optional) == self.mods.difference(self.optional)
+    }
+
+    /// Return true if `mods` and `key` match this chord. How the chord is
+    /// pressed is not considered.
+    pub fn matches<Q>(&self, mods: Modifiers, key: &Q) -> bool
+    where
+        K: PartialEq<Q>,
+    {
+        self.key.as_ref().is_some_and(|k| *k == *key) && self.matches_modifiers(mods)
+    }
+}
+
+impl<K> From<(Modifiers, K)> for Chord<K> {
+    fn from((mods, key): (Modifiers, K)) -> Self {
+        Chord::new(mods, key)
+    }
+}
+
+/// Return true if the chord sequence `chords` matches the `input` sequence of
+/// modifiers and keys.
+///
+/// ```
+/// use keyseq::{chord::matches_seq, Chord, Modifiers};
+/// let seq = [Chord::new(Modifiers::CONTROL, "X"),
+///            Chord::new(Modifiers::CONTROL, "S").with_optional(Modifiers::SHIFT)];
+/// assert!(matches_seq(&seq, &[(Modifiers::CONTROL, "X"),
+///                             (Modifiers::CONTROL | Modifiers::SHIFT, "S")]));
+/// assert!(!matches_seq(&seq, &[(Modifiers::CONTROL, "X")]));
+/// ```
+pub fn matches_seq<K, Q>(chords: &[Chord<K>], input: &[(Modifiers, Q)]) -> bool
+where
+    K: PartialEq<Q>,
+{
+    chords.len() == input.len()
+        && chords
+            .iter()
+            .zip(input)
+            .all(|(chord, (mods, key))| chord.matches(*mods, key))
+}
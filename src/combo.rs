@@ -0,0 +1,285 @@
+//! Detect combos, several keys pressed at once
+//!
+//! A combo like `J+K` fires when all of its keys are pressed within a
+//! simultaneity window. Keys that do not complete a combo are reported
+//! individually once it is clear they cannot be part of one.
+//!
+//! ```
+//! use std::time::Duration;
+//! use keyseq::{Modifiers, combo::{ComboDetector, ComboEvent}};
+//!
+//! let mut detector = ComboDetector::new(Duration::from_millis(50));
+//! let escape = detector.add((Modifiers::NONE, ["J", "K"]));
+//!
+//! detector.press(Modifiers::NONE, "J", Duration::from_millis(0));
+//! detector.press(Modifiers::NONE, "K", Duration::from_millis(20));
+//! assert_eq!(detector.drain().collect::<Vec<_>>(), [ComboEvent::Combo(escape)]);
+//!
+//! // Too slow; report the keys instead.
+//! detector.press(Modifiers::NONE, "J", Duration::from_millis(100));
+//! detector.update(Duration::from_millis(200));
+//! assert_eq!(detector.drain().collect::<Vec<_>>(),
+//!            [ComboEvent::Key(Modifiers::NONE, "J")]);
+//! ```
+use super::Modifiers;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The output of a [ComboDetector].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComboEvent<K> {
+    /// The combo with this index fired.
+    Combo(usize),
+    /// A key press that was not part of a combo.
+    Key(Modifiers, K),
+}
+
+#[derive(Clone, Debug)]
+struct Combo<K> {
+    mods: Modifiers,
+    keys: Vec<K>,
+}
+
+impl<K: PartialEq> Combo<K> {
+    fn contains_all<'a>(&self, keys: impl IntoIterator<Item = &'a K>) -> bool
+    where
+        K: 'a,
+    {
+        keys.into_iter().all(|k| self.keys.contains(k))
+    }
+}
+
+/// Detects combos, several non-modifier keys pressed within a simultaneity
+/// window.
+///
+/// Feed it key presses and releases with a timestamp, call
+/// [update](ComboDetector::update) each frame, and [drain](ComboDetector::drain)
+/// the resulting events.
+#[derive(Clone, Debug)]
+pub struct ComboDetector<K> {
+    window: Duration,
+    combos: Vec<Combo<K>>,
+    /// Pressed keys that may still become part of a combo.
+    pending: Vec<(Modifiers, K, Duration)>,
+    /// Keys consumed by a combo that are still held.
+    consumed: Vec<K>,
+    events: VecDeque<ComboEvent<K>>,
+}
+
+impl<K: Clone + PartialEq> ComboDetector<K> {
+    /// Create a detector with the given simultaneity window.
+    pub fn new(window: Duration) -> Self {
+        ComboDetector {
+            window,
+            combos: vec![],
+            pending: vec![],
+            consumed: vec![],
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Return the simultaneity window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Set the simultaneity window.
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Add a combo, e.g., `pcombo! { J+K }`, and return its index.
+    pub fn add<I: IntoIterator<Item = K>>(&mut self, (mods, keys): (Modifiers, I)) -> usize {
+        self.combos.push(Combo {
+            mods,
+            keys: keys.into_iter().collect(),
+        });
+        self.combos.len() - 1
+    }
+
+    /// Add a combo and return self.
+    pub fn with<I: IntoIterator<Item = K>>(mut self, combo: (Modifiers, I)) -> Self {
+        self.add(combo);
+        self
+    }
+
+    /// Record a key press at time `now`.
+    pub fn press(&mut self, mods: Modifiers, key: K, now: Duration) {
+        self.update(now);
+        if self.pending.iter().any(|(_, k, _)| *k == key) {
+            return;
+        }
+        self.pending.push((mods, key, now));
+        if !self.could_complete(mods) {
+            // The newest key may still start a combo of its own.
+            let (mods, key, time) = self.pending.pop().unwrap();
+            self.flush();
+            self.pending.push((mods, key, time));
+            if !self.could_complete(mods) {
+                self.flush();
+                return;
+            }
+        }
+        if let Some(index) = self.completed(mods) {
+            let larger_possible = self.combos.iter().any(|combo| {
+                combo.keys.len() > self.pending.len()
+                    && combo.mods == mods
+                    && combo.contains_all(self.pending.iter().map(|(_, k, _)| k))
+            });
+            if !larger_possible {
+                self.fire(index);
+            }
+        }
+    }
+
+    /// Record a key release at time `now`.
+    pub fn release(&mut self, key: &K, now: Duration) {
+        self.update(now);
+        if let Some(index) = self.consumed.iter().position(|k| k == key) {
+            self.consumed.swap_remove(index);
+        } else if self.pending.iter().any(|(_, k, _)| k == key) {
+            // Releasing a key ends any chance of a combo.
+            self.resolve();
+        }
+    }
+
+    /// Resolve pending keys whose window has elapsed by time `now`.
+    pub fn update(&mut self, now: Duration) {
+        if let Some((_, _, start)) = self.pending.first() {
+            if now.saturating_sub(*start) > self.window {
+                self.resolve();
+            }
+        }
+    }
+
+    /// Return true if keys are waiting to be resolved.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Drain the events that have been detected.
+    pub fn drain(&mut self) -> impl Iterator<Item = ComboEvent<K>> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Fire a completed combo or report the pending keys individually.
+    fn resolve(&mut self) {
+        let mods = self
+            .pending
+            .last()
+            .map(|(m, _, _)| *m)
+            .unwrap_or(Modifiers::NONE);
+        match self.completed(mods) {
+            Some(index) => self.fire(index),
+            None => self.flush(),
+        }
+    }
+
+    fn could_complete(&self, mods: Modifiers) -> bool {
+        self.combos.iter().any(|combo| {
+            combo.mods == mods && combo.contains_all(self.pending.iter().map(|(_, k, _)| k))
+        })
+    }
+
+    fn completed(&self, mods: Modifiers) -> Option<usize> {
+        self.combos.iter().position(|combo| {
+            combo.mods == mods
+                && combo.keys.len() == self.pending.len()
+                && combo.contains_all(self.pending.iter().map(|(_, k, _)| k))
+        })
+    }
+
+    fn fire(&mut self, index: usize) {
+        self.consumed
+            .extend(self.pending.drain(..).map(|(_, key, _)| key));
+        self.events.push_back(ComboEvent::Combo(index));
+    }
+
+    fn flush(&mut self) {
+        self.events.extend(
+            self.pending
+                .drain(..)
+                .map(|(mods, key, _)| ComboEvent::Key(mods, key)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn combo_fires_within_window() {
+        let mut detector = ComboDetector::new(ms(50)).with((Modifiers::NONE, ["J", "K"]));
+        detector.press(Modifiers::NONE, "K", ms(0));
+        assert!(detector.is_pending());
+        detector.press(Modifiers::NONE, "J", ms(49));
+        assert_eq!(detector.drain().collect::<Vec<_>>(), [ComboEvent::Combo(0)]);
+        // Releasing consumed keys does not report them.
+        detector.release(&"J", ms(60));
+        detector.release(&"K", ms(60));
+        assert_eq!(detector.drain().count(), 0);
+    }
+
+    #[test]
+    fn unrelated_key_flushes() {
+        let mut detector = ComboDetector::new(ms(50)).with((Modifiers::NONE, ["J", "K"]));
+        detector.press(Modifiers::NONE, "A", ms(0));
+        detector.press(Modifiers::NONE, "J", ms(10));
+        detector.press(Modifiers::NONE, "B", ms(20));
+        assert_eq!(
+            detector.drain().collect::<Vec<_>>(),
+            [
+                ComboEvent::Key(Modifiers::NONE, "A"),
+                ComboEvent::Key(Modifiers::NONE, "J"),
+                ComboEvent::Key(Modifiers::NONE, "B")
+            ]
+        );
+    }
+
+    #[test]
+    fn release_before_combo_reports_key() {
+        let mut detector = ComboDetector::new(ms(50)).with((Modifiers::NONE, ["J", "K"]));
+        detector.press(Modifiers::NONE, "J", ms(0));
+        detector.release(&"J", ms(10));
+        assert_eq!(
+            detector.drain().collect::<Vec<_>>(),
+            [ComboEvent::Key(Modifiers::NONE, "J")]
+        );
+    }
+
+    #[test]
+    fn larger_combo_waits() {
+        let mut detector = ComboDetector::new(ms(50))
+            .with((Modifiers::NONE, vec!["J", "K"]))
+            .with((Modifiers::NONE, vec!["J", "K", "L"]));
+        detector.press(Modifiers::NONE, "J", ms(0));
+        detector.press(Modifiers::NONE, "K", ms(10));
+        assert_eq!(detector.drain().count(), 0);
+        detector.update(ms(100));
+        assert_eq!(detector.drain().collect::<Vec<_>>(), [ComboEvent::Combo(0)]);
+
+        detector.press(Modifiers::NONE, "J", ms(200));
+        detector.press(Modifiers::NONE, "K", ms(210));
+        detector.press(Modifiers::NONE, "L", ms(220));
+        assert_eq!(detector.drain().collect::<Vec<_>>(), [ComboEvent::Combo(1)]);
+    }
+
+    #[test]
+    fn modifiers_must_match() {
+        let mut detector = ComboDetector::new(ms(50)).with((Modifiers::CONTROL, ["J", "K"]));
+        detector.press(Modifiers::NONE, "J", ms(0));
+        detector.press(Modifiers::NONE, "K", ms(10));
+        assert_eq!(
+            detector.drain().collect::<Vec<_>>(),
+            [
+                ComboEvent::Key(Modifiers::NONE, "J"),
+                ComboEvent::Key(Modifiers::NONE, "K")
+            ]
+        );
+    }
+}
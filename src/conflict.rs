@@ -0,0 +1,203 @@
+//! Find ambiguous key bindings
+//!
+//! Two bindings conflict when the same keys could trigger either one:
+//!
+//! - a duplicate binds the same key sequence twice;
+//! - a prefix binds a sequence that is the start of a longer one, e.g.,
+//!   `Ctrl-X` shadows `Ctrl-X Ctrl-S`;
+//! - a superset overlap, only with [MatchMode::Superset], binds chords that
+//!   both match when their modifiers are held together, but neither's
+//!   modifiers include the other's, e.g., `Ctrl-S` and `Alt-S` with Ctrl and
+//!   Alt held.
+//!
+//! With superset matching, `Ctrl-A` and `Ctrl-Shift-A` do not conflict since
+//! the binding with the most modifiers wins.
+//!
+//! ```
+//! use keyseq::{Modifiers, conflict::{find_conflicts, Conflict, ConflictKind, MatchMode}};
+//! let seqs: [&[(Modifiers, &str)]; 4] = [
+//!     &[(Modifiers::CONTROL, "X")],
+//!     &[(Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "S")],
+//!     &[(Modifiers::CONTROL | Modifiers::SHIFT, "X")],
+//!     &[(Modifiers::ALT, "X")],
+//! ];
+//! assert_eq!(find_conflicts(seqs, MatchMode::Exact),
+//!            [Conflict { kind: ConflictKind::Prefix, first: 0, second: 1 }]);
+//! assert_eq!(find_conflicts(seqs, MatchMode::Superset), [
+//!     Conflict { kind: ConflictKind::Prefix, first: 0, second: 1 },
+//!     Conflict { kind: ConflictKind::Prefix, first: 1, second: 2 },
+//!     Conflict { kind: ConflictKind::Superset, first: 0, second: 3 },
+//!     Conflict { kind: ConflictKind::Prefix, first: 1, second: 3 },
+//!     Conflict { kind: ConflictKind::Superset, first: 2, second: 3 },
+//! ]);
+//! ```
+use super::Modifiers;
+
+/// How held modifiers are compared with the modifiers of a binding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// The held modifiers must equal the bound modifiers.
+    #[default]
+    Exact,
+    /// The held modifiers must include the bound modifiers. The binding with
+    /// the most modifiers wins.
+    Superset,
+}
+
+impl MatchMode {
+    /// Return true if `held` modifiers satisfy the `bound` modifiers.
+    pub fn matches(self, bound: Modifiers, held: Modifiers) -> bool {
+        match self {
+            MatchMode::Exact => bound == held,
+            MatchMode::Superset => held.contains(bound),
+        }
+    }
+
+    /// Return true if the `input` sequence satisfies the `bound` sequence.
+    pub fn matches_seq<K: PartialEq>(
+        self,
+        bound: &[(Modifiers, K)],
+        input: &[(Modifiers, K)],
+    ) -> bool {
+        bound.len() == input.len()
+            && bound
+                .iter()
+                .zip(input)
+                .all(|((m, k), (held, key))| k == key && self.matches(*m, *held))
+    }
+}
+
+/// The kind of a [Conflict].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both bindings have the same key sequence.
+    Duplicate,
+    /// One binding's sequence is the start of the other's. With superset
+    /// matching, the start only needs to match the same held modifiers.
+    Prefix,
+    /// Both bindings match when their modifiers are held together, and
+    /// neither's modifiers include the other's, so neither has the most.
+    Superset,
+}
+
+/// Two bindings, given by index, that may be triggered by the same keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// What kind of conflict it is.
+    pub kind: ConflictKind,
+    /// The index of the binding declared first.
+    pub first: usize,
+    /// The index of the binding declared second.
+    pub second: usize,
+}
+
+/// Return the kind of conflict between sequences `a` and `b` if any.
+pub fn conflict_kind<K: PartialEq>(
+    a: &[(Modifiers, K)],
+    b: &[(Modifiers, K)],
+    mode: MatchMode,
+) -> Option<ConflictKind> {
+    let n = a.len().min(b.len());
+    if a[..n] == b[..n] {
+        return Some(if a.len() == b.len() {
+            ConflictKind::Duplicate
+        } else {
+            ConflictKind::Prefix
+        });
+    }
+    // Holding the modifiers of both matches both if the keys are the same.
+    if mode == MatchMode::Exact || a[..n].iter().zip(&b[..n]).any(|((_, k), (_, l))| k != l) {
+        return None;
+    }
+    if a.len() != b.len() {
+        return Some(ConflictKind::Prefix);
+    }
+    // Otherwise the binding whose modifiers include the other's wins.
+    let includes = |x: &[(Modifiers, K)], y: &[(Modifiers, K)]| {
+        x.iter().zip(y).all(|((m, _), (p, _))| m.contains(*p))
+    };
+    (!includes(a, b) && !includes(b, a)).then_some(ConflictKind::Superset)
+}
+
+/// Return every conflict among the key sequences `seqs`.
+pub fn find_conflicts<'a, K, I>(seqs: I, mode: MatchMode) -> Vec<Conflict>
+where
+    K: PartialEq + 'a,
+    I: IntoIterator<Item = &'a [(Modifiers, K)]>,
+{
+    let seqs: Vec<&[(Modifiers, K)]> = seqs.into_iter().collect();
+    let mut conflicts = vec![];
+    for (second, b) in seqs.iter().enumerate() {
+        for (first, a) in seqs[..second].iter().enumerate() {
+            if let Some(kind) = conflict_kind(a, b, mode) {
+                conflicts.push(Conflict {
+                    kind,
+                    first,
+                    second,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_either_order() {
+        let long = [(Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "S")];
+        let short = [(Modifiers::CONTROL, "X")];
+        assert_eq!(
+            conflict_kind(&long, &short, MatchMode::Exact),
+            Some(ConflictKind::Prefix)
+        );
+        assert_eq!(
+            conflict_kind(&short, &long, MatchMode::Exact),
+            Some(ConflictKind::Prefix)
+        );
+    }
+
+    #[test]
+    fn superset_only_in_superset_mode() {
+        let a = [(Modifiers::CONTROL, "A")];
+        let b = [(Modifiers::CONTROL | Modifiers::SHIFT, "A")];
+        let c = [(Modifiers::ALT, "A")];
+        let d = [(Modifiers::ALT, "B")];
+        assert_eq!(conflict_kind(&a, &c, MatchMode::Exact), None);
+        // The most modifiers wins.
+        assert_eq!(conflict_kind(&a, &b, MatchMode::Superset), None);
+        // Ctrl-Alt-A matches both equally.
+        assert_eq!(
+            conflict_kind(&a, &c, MatchMode::Superset),
+            Some(ConflictKind::Superset)
+        );
+        assert_eq!(
+            conflict_kind(&b, &c, MatchMode::Superset),
+            Some(ConflictKind::Superset)
+        );
+        assert_eq!(conflict_kind(&a, &d, MatchMode::Superset), None);
+        // Each has more modifiers in one chord.
+        let e = [
+            (Modifiers::CONTROL, "X"),
+            (Modifiers::CONTROL | Modifiers::SHIFT, "S"),
+        ];
+        let f = [
+            (Modifiers::CONTROL | Modifiers::SHIFT, "X"),
+            (Modifiers::CONTROL, "S"),
+        ];
+        assert_eq!(
+            conflict_kind(&e, &f, MatchMode::Superset),
+            Some(ConflictKind::Superset)
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let a = [(Modifiers::CONTROL, "A")];
+        let conflicts = find_conflicts([&a[..], &a[..], &a[..]], MatchMode::Exact);
+        assert_eq!(conflicts.len(), 3);
+        assert!(conflicts.iter().all(|c| c.kind == ConflictKind::Duplicate));
+    }
+}
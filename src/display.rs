@@ -0,0 +1,446 @@
+//! Display chords in the style of each platform
+//!
+//! `Modifiers` displays itself as `Ctrl-Alt-Shift-Super`, keyseq's own
+//! notation. Mac users expect `⌃⌥⇧⌘A` and Windows users expect `Ctrl+Alt+A`. A
+//! [Style] writes modifiers, chords, and sequences with each platform's names,
+//! separators, and modifier order.
+//!
+//! ```
+//! use keyseq::{Modifiers, display::Style};
+//! let mods = Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT | Modifiers::SUPER;
+//! assert_eq!(Style::Keyseq.format_chord(mods, "A"), "Ctrl-Alt-Shift-Super-A");
+//! assert_eq!(Style::Windows.format_chord(mods, "A"), "Win+Ctrl+Alt+Shift+A");
+//! assert_eq!(Style::MacGlyphs.format_chord(mods, "A"), "⌃⌥⇧⌘A");
+//! assert_eq!(Style::MacWords.format_chord(mods, "A"), "Control-Option-Shift-Command-A");
+//! assert_eq!(Style::Gnome.format_chord(mods, "A"), "Shift+Ctrl+Alt+Super+A");
+//! assert_eq!(Style::Emacs.format_chord(mods, "A"), "C-M-s-A");
+//!
+//! let seq = [(Modifiers::CONTROL, "K"), (Modifiers::CONTROL, "ArrowUp")];
+//! assert_eq!(Style::Windows.format_keyseq(&seq), "Ctrl+K, Ctrl+Up");
+//! assert_eq!(Style::MacGlyphs.format_keyseq(&seq), "⌃K ⌃↑");
+//! ```
+//!
+//! The names may be localized by [KeyNames], e.g., a built-in [Locale]. Names
+//! it does not supply fall back to the style's English names.
+//!
+//! ```
+//! use keyseq::{Modifiers, display::{Locale, Style}};
+//! let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+//! assert_eq!(Style::Windows.format_chord_with(&Locale::German, mods, "Delete"),
+//!            "Strg+Umschalt+Entf");
+//! assert_eq!(Style::Windows.format_chord_with(&Locale::French, mods, "F5"), "Ctrl+Maj+F5");
+//! ```
+use super::{notation::emacs, Modifiers};
+use std::collections::HashMap;
+
+/// Supplies localized display names for modifiers and keys.
+///
+/// Return `None` to fall back to the English name of the [Style].
+pub trait KeyNames {
+    /// Return the name of a single `modifier`, e.g., "Strg" for
+    /// [Modifiers::CONTROL].
+    fn modifier_name(&self, _modifier: Modifiers) -> Option<&str> {
+        None
+    }
+
+    /// Return the name of `key`, given in keyseq's notation, e.g., "Entf" for
+    /// "Delete".
+    fn key_name(&self, _key: &str) -> Option<&str> {
+        None
+    }
+}
+
+/// A lookup table from keyseq's names, e.g., "Ctrl" or "Delete", to display
+/// names.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use keyseq::{Modifiers, display::Style};
+/// let names = HashMap::from([("Shift".to_string(), "Maiusc".to_string())]);
+/// assert_eq!(Style::Keyseq.format_chord_with(&names, Modifiers::SHIFT, "A"), "Maiusc-A");
+/// ```
+impl KeyNames for HashMap<String, String> {
+    fn modifier_name(&self, modifier: Modifiers) -> Option<&str> {
+        self.get(Style::Keyseq.modifier_name(modifier))
+            .map(String::as_str)
+    }
+
+    fn key_name(&self, key: &str) -> Option<&str> {
+        self.get(key).map(String::as_str)
+    }
+}
+
+/// Built-in display names for common locales.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// English, which uses the names of each [Style].
+    #[default]
+    English,
+    /// German
+    German,
+    /// French
+    French,
+    /// Spanish
+    Spanish,
+    /// Italian
+    Italian,
+}
+
+/// The keys the built-in locales name. An empty name falls back to English.
+const LOCALIZED_KEYS: [&str; 11] = [
+    "Escape",
+    "Enter",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Space",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Tab",
+];
+
+impl Locale {
+    /// Every built-in locale.
+    pub const ALL: [Locale; 5] = [
+        Locale::English,
+        Locale::German,
+        Locale::French,
+        Locale::Spanish,
+        Locale::Italian,
+    ];
+
+    /// Return the locale of a language tag like "de" or "fr-CA".
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+        Some(match language.as_str() {
+            "en" => Locale::English,
+            "de" => Locale::German,
+            "fr" => Locale::French,
+            "es" => Locale::Spanish,
+            "it" => Locale::Italian,
+            _ => return None,
+        })
+    }
+
+    fn modifier_names(self) -> [&'static str; 3] {
+        match self {
+            Locale::English => ["", "", ""],
+            Locale::German => ["Strg", "Alt", "Umschalt"],
+            Locale::French => ["Ctrl", "Alt", "Maj"],
+            Locale::Spanish => ["Ctrl", "Alt", "Mayús"],
+            Locale::Italian => ["Ctrl", "Alt", "Maiusc"],
+        }
+    }
+
+    fn key_names(self) -> [&'static str; 11] {
+        match self {
+            Locale::English => [""; 11],
+            Locale::German => [
+                "Esc",
+                "Eingabe",
+                "Rücktaste",
+                "Entf",
+                "Einfg",
+                "Leertaste",
+                "Pos1",
+                "Ende",
+                "Bild auf",
+                "Bild ab",
+                "Tab",
+            ],
+            Locale::French => [
+                "Échap",
+                "Entrée",
+                "Retour arrière",
+                "Suppr",
+                "Inser",
+                "Espace",
+                "Début",
+                "Fin",
+                "Page préc.",
+                "Page suiv.",
+                "Tab",
+            ],
+            Locale::Spanish => [
+                "Esc",
+                "Intro",
+                "Retroceso",
+                "Supr",
+                "Insert",
+                "Espacio",
+                "Inicio",
+                "Fin",
+                "Re Pág",
+                "Av Pág",
+                "Tab",
+            ],
+            Locale::Italian => [
+                "Esc",
+                "Invio",
+                "Backspace",
+                "Canc",
+                "Ins",
+                "Spazio",
+                "Home",
+                "Fine",
+                "Pag su",
+                "Pag giù",
+                "Tab",
+            ],
+        }
+    }
+}
+
+impl KeyNames for Locale {
+    fn modifier_name(&self, modifier: Modifiers) -> Option<&str> {
+        let index = match modifier {
+            Modifiers::CONTROL => 0,
+            Modifiers::ALT => 1,
+            Modifiers::SHIFT => 2,
+            _ => return None,
+        };
+        Some(self.modifier_names()[index]).filter(|name| !name.is_empty())
+    }
+
+    fn key_name(&self, key: &str) -> Option<&str> {
+        let index = LOCALIZED_KEYS.iter().position(|k| *k == key)?;
+        Some(self.key_names()[index]).filter(|name| !name.is_empty())
+    }
+}
+
+/// How to display chords.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// keyseq's notation, e.g., `Ctrl-Shift-A`, as `Modifiers` displays.
+    #[default]
+    Keyseq,
+    /// Windows, e.g., `Ctrl+Shift+A`.
+    Windows,
+    /// macOS menu glyphs, e.g., `⇧⌘A`.
+    MacGlyphs,
+    /// macOS words, e.g., `Shift-Command-A`.
+    MacWords,
+    /// GNOME and GTK, e.g., `Shift+Ctrl+A`.
+    Gnome,
+    /// Emacs, e.g., `C-S-a`.
+    Emacs,
+}
+
+/// Key names that some styles display differently.
+const KEYS: &[(&str, [&str; 4])] = &[
+    // (keyseq, [Windows, MacGlyphs, MacWords, Gnome])
+    ("Escape", ["Esc", "⎋", "Escape", "Esc"]),
+    ("Enter", ["Enter", "↩", "Return", "Return"]),
+    ("Tab", ["Tab", "⇥", "Tab", "Tab"]),
+    ("Backspace", ["Backspace", "⌫", "Delete", "BackSpace"]),
+    ("Delete", ["Del", "⌦", "Forward Delete", "Delete"]),
+    ("Space", ["Space", "Space", "Space", "Space"]),
+    ("ArrowUp", ["Up", "↑", "Up Arrow", "Up"]),
+    ("ArrowDown", ["Down", "↓", "Down Arrow", "Down"]),
+    ("ArrowLeft", ["Left", "←", "Left Arrow", "Left"]),
+    ("ArrowRight", ["Right", "→", "Right Arrow", "Right"]),
+    ("PageUp", ["PgUp", "⇞", "Page Up", "Page Up"]),
+    ("PageDown", ["PgDn", "⇟", "Page Down", "Page Down"]),
+    ("Home", ["Home", "↖", "Home", "Home"]),
+    ("End", ["End", "↘", "End", "End"]),
+];
+
+impl Style {
+    /// Return the usual style of the platform this was compiled for.
+    pub fn native() -> Style {
+        if cfg!(target_os = "macos") {
+            Style::MacGlyphs
+        } else if cfg!(target_os = "windows") {
+            Style::Windows
+        } else {
+            Style::Gnome
+        }
+    }
+
+    /// Return the modifiers in the order this style writes them.
+    pub fn modifier_order(self) -> [Modifiers; 4] {
+        use Modifiers as M;
+        match self {
+            Style::Keyseq | Style::Emacs => [M::CONTROL, M::ALT, M::SHIFT, M::SUPER],
+            Style::Windows => [M::SUPER, M::CONTROL, M::ALT, M::SHIFT],
+            // Apple's Human Interface Guidelines order.
+            Style::MacGlyphs | Style::MacWords => [M::CONTROL, M::ALT, M::SHIFT, M::SUPER],
+            Style::Gnome => [M::SHIFT, M::CONTROL, M::ALT, M::SUPER],
+        }
+    }
+
+    /// Return the name of a single `modifier`.
+    pub fn modifier_name(self, modifier: Modifiers) -> &'static str {
+        let names = match self {
+            Style::Keyseq => ["Ctrl", "Alt", "Shift", "Super"],
+            Style::Windows => ["Ctrl", "Alt", "Shift", "Win"],
+            Style::MacGlyphs => ["⌃", "⌥", "⇧", "⌘"],
+            Style::MacWords => ["Control", "Option", "Shift", "Command"],
+            Style::Gnome => ["Ctrl", "Alt", "Shift", "Super"],
+            Style::Emacs => ["C", "M", "S", "s"],
+        };
+        match modifier {
+            Modifiers::CONTROL => names[0],
+            Modifiers::ALT => names[1],
+            Modifiers::SHIFT => names[2],
+            Modifiers::SUPER => names[3],
+            _ => "",
+        }
+    }
+
+    /// Return the name of `key` in this style.
+    pub fn key_name(self, key: &str) -> &str {
+        let column = match self {
+            Style::Windows => 0,
+            Style::MacGlyphs => 1,
+            Style::MacWords => 2,
+            Style::Gnome => 3,
+            Style::Keyseq | Style::Emacs => return key,
+        };
+        KEYS.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, names)| names[column])
+            .unwrap_or(key)
+    }
+
+    /// Return the separator after each modifier.
+    fn separator(self) -> &'static str {
+        match self {
+            Style::Keyseq | Style::MacWords | Style::Emacs => "-",
+            Style::Windows | Style::Gnome => "+",
+            Style::MacGlyphs => "",
+        }
+    }
+
+    /// Return the separator between the chords of a sequence.
+    fn sequence_separator(self) -> &'static str {
+        match self {
+            Style::Windows => ", ",
+            _ => " ",
+        }
+    }
+
+    /// Return true if `names` apply to this style. Glyphs and Emacs notation
+    /// are not localized.
+    fn is_localized(self) -> bool {
+        !matches!(self, Style::MacGlyphs | Style::Emacs)
+    }
+
+    /// Write the modifiers, e.g., `Ctrl+Shift`.
+    pub fn format_modifiers(self, mods: Modifiers) -> String {
+        self.format_modifiers_with(&Locale::English, mods)
+    }
+
+    /// Write the modifiers with localized `names`.
+    pub fn format_modifiers_with<N: KeyNames + ?Sized>(self, names: &N, mods: Modifiers) -> String {
+        self.modifier_order()
+            .into_iter()
+            .filter(|m| mods.contains(*m))
+            .map(|m| self.localized_modifier(names, m))
+            .collect::<Vec<_>>()
+            .join(self.separator())
+    }
+
+    fn localized_modifier<N: KeyNames + ?Sized>(self, names: &N, modifier: Modifiers) -> &str {
+        names
+            .modifier_name(modifier)
+            .filter(|_| self.is_localized())
+            .unwrap_or(self.modifier_name(modifier))
+    }
+
+    /// Write a chord, e.g., `Ctrl+Shift+A`.
+    pub fn format_chord(self, mods: Modifiers, key: &str) -> String {
+        self.format_chord_with(&Locale::English, mods, key)
+    }
+
+    /// Write a chord with localized `names`.
+    pub fn format_chord_with<N: KeyNames + ?Sized>(
+        self,
+        names: &N,
+        mods: Modifiers,
+        key: &str,
+    ) -> String {
+        if self == Style::Emacs {
+            return emacs::format_chord(mods, key);
+        }
+        let mut result = String::new();
+        for modifier in self.modifier_order() {
+            if mods.contains(modifier) {
+                result.push_str(self.localized_modifier(names, modifier));
+                result.push_str(self.separator());
+            }
+        }
+        let localized = names.key_name(key).filter(|_| self.is_localized());
+        result.push_str(localized.unwrap_or(self.key_name(key)));
+        result
+    }
+
+    /// Write a key sequence, e.g., `Ctrl+K, Ctrl+S`.
+    pub fn format_keyseq<S: AsRef<str>>(self, seq: &[(Modifiers, S)]) -> String {
+        self.format_keyseq_with(&Locale::English, seq)
+    }
+
+    /// Write a key sequence with localized `names`.
+    pub fn format_keyseq_with<N: KeyNames + ?Sized, S: AsRef<str>>(
+        self,
+        names: &N,
+        seq: &[(Modifiers, S)],
+    ) -> String {
+        seq.iter()
+            .map(|(mods, key)| self.format_chord_with(names, *mods, key.as_ref()))
+            .collect::<Vec<_>>()
+            .join(self.sequence_separator())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyseq_style_matches_display() {
+        let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+        assert_eq!(Style::Keyseq.format_modifiers(mods), mods.to_string());
+        assert_eq!(Style::Keyseq.format_chord(Modifiers::NONE, "F5"), "F5");
+    }
+
+    #[test]
+    fn key_names() {
+        assert_eq!(
+            Style::MacGlyphs.format_chord(Modifiers::SUPER, "Enter"),
+            "⌘↩"
+        );
+        assert_eq!(
+            Style::MacWords.format_chord(Modifiers::ALT, "Backspace"),
+            "Option-Delete"
+        );
+        assert_eq!(Style::Emacs.format_chord(Modifiers::CONTROL, "X"), "C-x");
+        assert_eq!(Style::Windows.format_modifiers(Modifiers::NONE), "");
+    }
+
+    #[test]
+    fn localized_names_fall_back_to_english() {
+        for locale in Locale::ALL {
+            assert_eq!(
+                Style::Keyseq.format_chord_with(&locale, Modifiers::SUPER, "F1"),
+                "Super-F1"
+            );
+        }
+        assert_eq!(
+            Style::Gnome.format_keyseq_with(
+                &Locale::Spanish,
+                &[(Modifiers::SHIFT, "Enter"), (Modifiers::NONE, "ArrowUp")]
+            ),
+            "Mayús+Intro Up"
+        );
+        // Glyphs are not localized.
+        assert_eq!(
+            Style::MacGlyphs.format_chord_with(&Locale::German, Modifiers::SHIFT, "Delete"),
+            "⇧⌦"
+        );
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("ja"), None);
+    }
+}
@@ -0,0 +1,678 @@
+//! A runtime keymap with layered overrides
+//!
+//! A game ships default bindings, plugins add their own, and players override
+//! them. A [Keymap] stacks these as layers. A later layer takes priority over an
+//! earlier one and may explicitly unbind a key sequence. Only a layer's
+//! difference from the layers beneath it needs to be saved.
+//!
+//! ```
+//! use keyseq::{Modifiers, keymap::{Change, Keymap}};
+//!
+//! let mut keymap = Keymap::new();
+//! let defaults = keymap.add_layer("defaults");
+//! let user = keymap.add_layer("user");
+//! keymap.bind(defaults, [(Modifiers::CONTROL, "S")], "save");
+//! keymap.bind(defaults, [(Modifiers::CONTROL, "Q")], "quit");
+//!
+//! keymap.bind(user, [(Modifiers::CONTROL, "S")], "save-all");
+//! keymap.unbind(user, [(Modifiers::CONTROL, "Q")]);
+//!
+//! assert_eq!(keymap.resolve(&[(Modifiers::CONTROL, "S")]), Some(&"save-all"));
+//! assert_eq!(keymap.resolve(&[(Modifiers::CONTROL, "Q")]), None);
+//! assert_eq!(keymap.diff(user),
+//!            [Change::Bind(vec![(Modifiers::CONTROL, "S")], "save-all"),
+//!             Change::Unbind(vec![(Modifiers::CONTROL, "Q")])]);
+//! ```
+use super::{
+    bindings::{modifier_count, KeyBindings},
+    conflict::{find_conflicts, Conflict, MatchMode},
+    Modifiers,
+};
+use std::sync::OnceLock;
+
+/// A key sequence.
+pub type KeySeq<K> = Vec<(Modifiers, K)>;
+
+/// A key type with a placeholder for the leader chord, e.g., `Leader` in
+/// `pkeyseq! { Leader F S }`. See [Keymap::set_leader].
+pub trait Leader {
+    /// Return the placeholder key.
+    fn leader() -> Self;
+}
+
+impl Leader for &str {
+    fn leader() -> Self {
+        "Leader"
+    }
+}
+
+impl Leader for String {
+    fn leader() -> Self {
+        "Leader".to_string()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry<K, A> {
+    /// The key sequence as bound, which may contain the leader placeholder.
+    bound: KeySeq<K>,
+    /// The key sequence with the leader substituted.
+    keys: KeySeq<K>,
+    /// The action; `None` unbinds the sequence.
+    action: Option<A>,
+}
+
+/// A set of bindings that may override the layers beneath it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer<K, A> {
+    /// The name of the layer, e.g., "defaults" or "user".
+    pub name: String,
+    entries: Vec<Entry<K, A>>,
+}
+
+impl<K: PartialEq, A> Layer<K, A> {
+    /// Return the key sequences, with the leader substituted, and their
+    /// actions, `None` when unbound. When two entries have the same keys, the
+    /// later one takes priority.
+    pub fn entries(&self) -> impl Iterator<Item = (&[(Modifiers, K)], Option<&A>)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.keys.as_slice(), entry.action.as_ref()))
+    }
+
+    /// Return the index of the entry that decides `keys`, the last one with
+    /// those keys.
+    fn position(&self, keys: &[(Modifiers, K)]) -> Option<usize> {
+        self.entries
+            .iter()
+            .rposition(|entry| entry.keys.as_slice() == keys)
+    }
+
+    fn get(&self, keys: &[(Modifiers, K)]) -> Option<&Option<A>> {
+        self.position(keys).map(|index| &self.entries[index].action)
+    }
+
+    /// Set the entry for its bound keys. Entries bound to other keys are kept
+    /// even if they are the same after substituting the leader, so setting
+    /// another leader brings them back. The entry moves after any of those
+    /// so it takes priority.
+    fn set(&mut self, entry: Entry<K, A>) {
+        match self.entries.iter().position(|e| e.bound == entry.bound) {
+            Some(index)
+                if !self.entries[index + 1..]
+                    .iter()
+                    .any(|e| e.keys == entry.keys) =>
+            {
+                self.entries[index] = entry
+            }
+            Some(index) => {
+                self.entries.remove(index);
+                self.entries.push(entry);
+            }
+            None => self.entries.push(entry),
+        }
+    }
+}
+
+/// A change relative to a base layer, see [Keymap::diff].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change<K, A> {
+    /// Bind the key sequence to the action.
+    Bind(KeySeq<K>, A),
+    /// Unbind the key sequence.
+    Unbind(KeySeq<K>),
+}
+
+/// A chord that continues a key sequence in progress, see
+/// [Keymap::continuations].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Continuation<K, A> {
+    /// The next chord.
+    pub chord: (Modifiers, K),
+    /// The action bound to the sequence that ends with this chord.
+    pub action: Option<A>,
+    /// The number of longer bindings that continue past this chord.
+    pub more: usize,
+}
+
+/// Return the continuations of `prefix` among `bindings` in the order they
+/// were first bound. `matches` compares the start of a binding to `prefix`.
+pub(crate) fn continuations<'a, K, A, I>(
+    bindings: I,
+    prefix: &[(Modifiers, K)],
+    matches: impl Fn(&[(Modifiers, K)], &[(Modifiers, K)]) -> bool,
+) -> Vec<Continuation<K, A>>
+where
+    K: Clone + PartialEq + 'a,
+    I: IntoIterator<Item = (&'a [(Modifiers, K)], A)>,
+{
+    let mut result: Vec<Continuation<K, A>> = vec![];
+    for (keys, action) in bindings {
+        if keys.len() <= prefix.len() || !matches(&keys[..prefix.len()], prefix) {
+            continue;
+        }
+        let chord = &keys[prefix.len()];
+        let index = match result.iter().position(|c| c.chord == *chord) {
+            Some(index) => index,
+            None => {
+                result.push(Continuation {
+                    chord: chord.clone(),
+                    action: None,
+                    more: 0,
+                });
+                result.len() - 1
+            }
+        };
+        if keys.len() == prefix.len() + 1 {
+            result[index].action = Some(action);
+        } else {
+            result[index].more += 1;
+        }
+    }
+    result
+}
+
+/// Key bindings in layers of increasing priority.
+#[derive(Clone, Debug)]
+pub struct Keymap<K, A> {
+    layers: Vec<Layer<K, A>>,
+    /// The leader placeholder and the chord it stands for.
+    leader: Option<(K, (Modifiers, K))>,
+    /// The layer and entry index of each effective binding, cleared when the
+    /// bindings change.
+    effective: OnceLock<Vec<(usize, usize)>>,
+    /// How held modifiers are matched.
+    pub match_mode: MatchMode,
+}
+
+impl<K, A> Default for Keymap<K, A> {
+    fn default() -> Self {
+        Keymap {
+            layers: vec![],
+            leader: None,
+            effective: OnceLock::new(),
+            match_mode: MatchMode::Exact,
+        }
+    }
+}
+
+impl<K: Clone + PartialEq, A: Clone + PartialEq> Keymap<K, A> {
+    /// Create an empty keymap with no layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer above the others and return its index.
+    pub fn add_layer(&mut self, name: impl Into<String>) -> usize {
+        self.layers.push(Layer {
+            name: name.into(),
+            entries: vec![],
+        });
+        self.layers.len() - 1
+    }
+
+    /// Return the index of the layer named `name`.
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    /// Return the layers from lowest to highest priority.
+    pub fn layers(&self) -> &[Layer<K, A>] {
+        &self.layers
+    }
+
+    /// Bind `keys` to `action` in `layer`.
+    pub fn bind(
+        &mut self,
+        layer: usize,
+        keys: impl IntoIterator<Item = (Modifiers, K)>,
+        action: A,
+    ) {
+        let bound: KeySeq<K> = keys.into_iter().collect();
+        let keys = substitute(&self.leader, &bound);
+        self.layers[layer].set(Entry {
+            bound,
+            keys,
+            action: Some(action),
+        });
+        self.effective.take();
+    }
+
+    /// Unbind `keys` in `layer`, which hides any binding beneath it.
+    pub fn unbind(&mut self, layer: usize, keys: impl IntoIterator<Item = (Modifiers, K)>) {
+        let bound: KeySeq<K> = keys.into_iter().collect();
+        let keys = substitute(&self.leader, &bound);
+        self.layers[layer].set(Entry {
+            bound,
+            keys,
+            action: None,
+        });
+        self.effective.take();
+    }
+
+    /// Remove any binding or unbinding of `keys` from `layer`, so the layers
+    /// beneath it show through.
+    pub fn reset(&mut self, layer: usize, keys: &[(Modifiers, K)]) {
+        let keys = substitute(&self.leader, keys);
+        self.layers[layer]
+            .entries
+            .retain(|entry| entry.keys != keys);
+        self.effective.take();
+    }
+
+    /// Remove every entry from `layer`.
+    pub fn clear(&mut self, layer: usize) {
+        self.layers[layer].entries.clear();
+        self.effective.take();
+    }
+
+    /// Add the default bindings of `B` to `layer`.
+    pub fn bind_all<B>(&mut self, layer: usize)
+    where
+        B: KeyBindings<Key = K> + Into<A>,
+        K: 'static,
+    {
+        for binding in B::bindings() {
+            self.bind(layer, binding.keys.iter().cloned(), binding.action.into());
+        }
+    }
+
+    /// Return the effective bindings, each key sequence with the action of the
+    /// highest layer that mentions it, in the order they were first bound.
+    pub fn effective(&self) -> Vec<(&[(Modifiers, K)], &A)> {
+        self.effective_iter().collect()
+    }
+
+    fn effective_iter(&self) -> impl DoubleEndedIterator<Item = (&[(Modifiers, K)], &A)> {
+        self.effective
+            .get_or_init(|| self.find_effective())
+            .iter()
+            .filter_map(|&(layer, index)| {
+                let entry = &self.layers[layer].entries[index];
+                Some((entry.keys.as_slice(), entry.action.as_ref()?))
+            })
+    }
+
+    fn find_effective(&self) -> Vec<(usize, usize)> {
+        let mut result = vec![];
+        let mut seen: Vec<&[(Modifiers, K)]> = vec![];
+        for layer in &self.layers {
+            for entry in &layer.entries {
+                if seen.contains(&entry.keys.as_slice()) {
+                    continue;
+                }
+                seen.push(&entry.keys);
+                // The highest layer that mentions the keys decides.
+                let top = self
+                    .layers
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(i, layer)| Some((i, layer.position(&entry.keys)?)));
+                if let Some((i, index)) = top {
+                    if self.layers[i].entries[index].action.is_some() {
+                        result.push((i, index));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Return the action bound to exactly `keys` by the highest layer.
+    fn resolve_exact(&self, keys: &[(Modifiers, K)]) -> Option<&A> {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.get(keys))
+            .and_then(|action| action.as_ref())
+    }
+
+    /// Return the action bound to the key sequence `seq`.
+    pub fn resolve(&self, seq: &[(Modifiers, K)]) -> Option<&A> {
+        match self.match_mode {
+            MatchMode::Exact => self.resolve_exact(seq),
+            MatchMode::Superset => self
+                .effective_iter()
+                .rev()
+                .filter(|(keys, _)| self.match_mode.matches_seq(keys, seq))
+                .max_by_key(|(keys, _)| modifier_count(keys))
+                .map(|(_, action)| action),
+        }
+    }
+
+    /// Return the action bound to the single chord `mods` and `key`.
+    pub fn resolve_chord(&self, mods: Modifiers, key: K) -> Option<&A> {
+        self.resolve(&[(mods, key)])
+    }
+
+    /// Return the action whose key sequence ends `history` and its length. The
+    /// longest such sequence wins.
+    pub fn resolve_suffix(&self, history: &[(Modifiers, K)]) -> Option<(&A, usize)> {
+        (1..=history.len()).rev().find_map(|n| {
+            self.resolve(&history[history.len() - n..])
+                .map(|action| (action, n))
+        })
+    }
+
+    /// Return true if `seq` is the start of a longer bound key sequence.
+    pub fn is_prefix(&self, seq: &[(Modifiers, K)]) -> bool {
+        self.effective_iter().any(|(keys, _)| {
+            keys.len() > seq.len() && self.match_mode.matches_seq(&keys[..seq.len()], seq)
+        })
+    }
+
+    /// Return the chords that may follow `prefix`, each with its action or the
+    /// number of longer bindings behind it, e.g., to list what can be typed
+    /// after `Ctrl-X`.
+    ///
+    /// ```
+    /// use keyseq::{Modifiers, keymap::{Continuation, Keymap}};
+    /// let mut keymap = Keymap::new();
+    /// let layer = keymap.add_layer("defaults");
+    /// keymap.bind(layer, [(Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "S")], "save");
+    /// keymap.bind(layer, [(Modifiers::CONTROL, "X"), (Modifiers::NONE, "R"), (Modifiers::NONE, "M")], "bookmark");
+    /// keymap.bind(layer, [(Modifiers::CONTROL, "X"), (Modifiers::NONE, "R"), (Modifiers::NONE, "J")], "jump");
+    /// assert_eq!(keymap.continuations(&[(Modifiers::CONTROL, "X")]), [
+    ///     Continuation { chord: (Modifiers::CONTROL, "S"), action: Some(&"save"), more: 0 },
+    ///     Continuation { chord: (Modifiers::NONE, "R"), action: None, more: 2 },
+    /// ]);
+    /// ```
+    pub fn continuations(&self, prefix: &[(Modifiers, K)]) -> Vec<Continuation<K, &A>> {
+        continuations(self.effective_iter(), prefix, |start, prefix| {
+            self.match_mode.matches_seq(start, prefix)
+        })
+    }
+
+    /// Return the ambiguous effective bindings. The indices refer to
+    /// [effective()](Keymap::effective).
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        find_conflicts(self.effective_iter().map(|(keys, _)| keys), self.match_mode)
+    }
+
+    /// Return the changes `layer` makes to the layers beneath it, e.g., the
+    /// user's changes to the defaults and plugins. Entries that repeat what is
+    /// beneath are left out, and key sequences are as bound, so they keep the
+    /// leader placeholder.
+    pub fn diff(&self, layer: usize) -> Vec<Change<K, A>> {
+        let beneath = |keys: &[(Modifiers, K)]| {
+            self.layers[..layer]
+                .iter()
+                .rev()
+                .find_map(|layer| layer.get(keys))
+                .and_then(|action| action.as_ref())
+        };
+        self.layers[layer]
+            .entries
+            .iter()
+            .filter_map(|entry| match (&entry.action, beneath(&entry.keys)) {
+                (Some(action), below) if below != Some(action) => {
+                    Some(Change::Bind(entry.bound.clone(), action.clone()))
+                }
+                (None, Some(_)) => Some(Change::Unbind(entry.bound.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Apply `changes`, e.g., from [diff()](Keymap::diff), to `layer`.
+    pub fn apply(&mut self, layer: usize, changes: impl IntoIterator<Item = Change<K, A>>) {
+        for change in changes {
+            match change {
+                Change::Bind(keys, action) => self.bind(layer, keys, action),
+                Change::Unbind(keys) => self.unbind(layer, keys),
+            }
+        }
+    }
+}
+
+impl<K: Leader + Clone + PartialEq, A: Clone + PartialEq> Keymap<K, A> {
+    /// Make the [Leader] placeholder in key sequences stand for the chord
+    /// `mods` and `key`. Modifiers on the placeholder are added to the
+    /// chord's. The bindings that use the placeholder are rebuilt, so they
+    /// need not be bound again. If a rebuilt binding has the same keys as
+    /// another in its layer, the one listed later in [Layer::entries] takes
+    /// priority; both are kept, so another leader separates them again.
+    ///
+    /// ```
+    /// use keyseq::{Modifiers, keymap::Keymap};
+    /// let mut keymap = Keymap::new();
+    /// let layer = keymap.add_layer("defaults");
+    /// keymap.bind(layer, [(Modifiers::NONE, "Leader"), (Modifiers::NONE, "F"), (Modifiers::NONE, "S")], "save");
+    /// keymap.set_leader(Modifiers::NONE, "Space");
+    /// assert_eq!(keymap.resolve(&[(Modifiers::NONE, "Space"), (Modifiers::NONE, "F"), (Modifiers::NONE, "S")]),
+    ///            Some(&"save"));
+    /// keymap.set_leader(Modifiers::CONTROL, "Space");
+    /// assert_eq!(keymap.resolve(&[(Modifiers::CONTROL, "Space"), (Modifiers::NONE, "F"), (Modifiers::NONE, "S")]),
+    ///            Some(&"save"));
+    /// ```
+    pub fn set_leader(&mut self, mods: Modifiers, key: K) {
+        let placeholder = K::leader();
+        let leader = Some((placeholder, (mods, key)));
+        for layer in &mut self.layers {
+            for entry in &mut layer.entries {
+                entry.keys = substitute(&leader, &entry.bound);
+            }
+        }
+        self.leader = leader;
+        self.effective.take();
+    }
+
+    /// Return the chord the [Leader] placeholder stands for.
+    pub fn leader(&self) -> Option<&(Modifiers, K)> {
+        self.leader.as_ref().map(|(_, chord)| chord)
+    }
+}
+
+/// Replace the leader placeholder in `keys` with its chord.
+fn substitute<K: Clone + PartialEq>(
+    leader: &Option<(K, (Modifiers, K))>,
+    keys: &[(Modifiers, K)],
+) -> KeySeq<K> {
+    keys.iter()
+        .map(|(mods, key)| match leader {
+            Some((placeholder, (leader_mods, leader_key))) if key == placeholder => {
+                (*mods | *leader_mods, leader_key.clone())
+            }
+            _ => (*mods, key.clone()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(key: &'static str) -> [(Modifiers, &'static str); 1] {
+        [(Modifiers::CONTROL, key)]
+    }
+
+    #[test]
+    fn layers_override_by_priority() {
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let plugins = keymap.add_layer("plugins");
+        let user = keymap.add_layer("user");
+        keymap.bind(defaults, chord("A"), 1);
+        keymap.bind(plugins, chord("A"), 2);
+        keymap.bind(plugins, chord("B"), 3);
+        assert_eq!(keymap.resolve(&chord("A")), Some(&2));
+        keymap.unbind(user, chord("B"));
+        assert_eq!(keymap.resolve(&chord("B")), None);
+        keymap.reset(user, &chord("B"));
+        assert_eq!(keymap.resolve(&chord("B")), Some(&3));
+        assert_eq!(keymap.layer_index("user"), Some(user));
+    }
+
+    #[test]
+    fn diff_round_trips() {
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let user = keymap.add_layer("user");
+        keymap.bind(defaults, chord("A"), 1);
+        keymap.bind(defaults, chord("B"), 2);
+        keymap.bind(user, chord("A"), 1);
+        keymap.bind(user, chord("C"), 3);
+        keymap.unbind(user, chord("B"));
+        let changes = keymap.diff(user);
+        // Rebinding to the default is not a change.
+        assert_eq!(
+            changes,
+            [
+                Change::Bind(chord("C").to_vec(), 3),
+                Change::Unbind(chord("B").to_vec())
+            ]
+        );
+
+        let mut restored = Keymap::new();
+        let defaults = restored.add_layer("defaults");
+        let user = restored.add_layer("user");
+        restored.bind(defaults, chord("A"), 1);
+        restored.bind(defaults, chord("B"), 2);
+        restored.apply(user, changes);
+        assert_eq!(restored.effective(), keymap.effective());
+    }
+
+    #[test]
+    fn diff_leaves_out_lower_layers() {
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let plugins = keymap.add_layer("plugins");
+        let user = keymap.add_layer("user");
+        keymap.bind(defaults, chord("A"), 1);
+        keymap.bind(plugins, chord("A"), 2);
+        keymap.bind(plugins, chord("B"), 3);
+        keymap.bind(user, chord("A"), 2);
+        keymap.bind(user, chord("C"), 4);
+        assert_eq!(keymap.diff(user), [Change::Bind(chord("C").to_vec(), 4)]);
+        assert_eq!(
+            keymap.diff(plugins),
+            [
+                Change::Bind(chord("A").to_vec(), 2),
+                Change::Bind(chord("B").to_vec(), 3)
+            ]
+        );
+        // The cached effective bindings follow changes.
+        assert_eq!(keymap.resolve(&chord("C")), Some(&4));
+        keymap.clear(user);
+        assert_eq!(keymap.resolve(&chord("C")), None);
+        assert_eq!(keymap.effective().len(), 2);
+    }
+
+    #[test]
+    fn sequences_and_conflicts() {
+        let mut keymap = Keymap::new();
+        let layer = keymap.add_layer("defaults");
+        keymap.bind(
+            layer,
+            [(Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "S")],
+            1,
+        );
+        keymap.bind(layer, chord("X"), 2);
+        let history = [
+            (Modifiers::NONE, "A"),
+            (Modifiers::CONTROL, "X"),
+            (Modifiers::CONTROL, "S"),
+        ];
+        assert_eq!(keymap.resolve_suffix(&history), Some((&1, 2)));
+        assert!(keymap.is_prefix(&chord("X")));
+        assert_eq!(keymap.conflicts().len(), 1);
+    }
+
+    #[test]
+    fn continuations_follow_overrides() {
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let user = keymap.add_layer("user");
+        let x = (Modifiers::CONTROL, "X");
+        keymap.bind(defaults, [x, (Modifiers::CONTROL, "S")], 1);
+        keymap.bind(defaults, [x, (Modifiers::CONTROL, "C")], 2);
+        keymap.bind(user, [x, (Modifiers::CONTROL, "S")], 3);
+        keymap.unbind(user, [x, (Modifiers::CONTROL, "C")]);
+        // A chord both bound and a prefix has an action and more.
+        keymap.bind(
+            user,
+            [x, (Modifiers::CONTROL, "S"), (Modifiers::NONE, "A")],
+            4,
+        );
+        assert_eq!(
+            keymap.continuations(&[x]),
+            [Continuation {
+                chord: (Modifiers::CONTROL, "S"),
+                action: Some(&3),
+                more: 1
+            }]
+        );
+        assert!(keymap.continuations(&[x, x]).is_empty());
+        assert_eq!(keymap.continuations(&[]).len(), 1);
+    }
+
+    #[test]
+    fn leader_keeps_placeholder() {
+        let leader = (Modifiers::NONE, "Leader");
+        let f = (Modifiers::NONE, "F");
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let user = keymap.add_layer("user");
+        keymap.bind(defaults, [leader, f], 1);
+        keymap.bind(defaults, [(Modifiers::CONTROL, "Leader")], 2);
+        // Without a leader, the placeholder is an ordinary key.
+        assert_eq!(keymap.resolve(&[leader, f]), Some(&1));
+        keymap.set_leader(Modifiers::NONE, "Space");
+        assert_eq!(keymap.leader(), Some(&(Modifiers::NONE, "Space")));
+        assert_eq!(keymap.resolve(&[(Modifiers::NONE, "Space"), f]), Some(&1));
+        assert_eq!(keymap.resolve(&[(Modifiers::CONTROL, "Space")]), Some(&2));
+        assert!(keymap.is_prefix(&[(Modifiers::NONE, "Space")]));
+
+        // Overrides and diffs keep the placeholder.
+        keymap.bind(user, [leader, f], 3);
+        keymap.unbind(user, [(Modifiers::CONTROL, "Leader")]);
+        keymap.set_leader(Modifiers::NONE, ",");
+        assert_eq!(keymap.resolve(&[(Modifiers::NONE, ","), f]), Some(&3));
+        assert_eq!(keymap.resolve(&[(Modifiers::CONTROL, ",")]), None);
+        assert_eq!(
+            keymap.diff(user),
+            [
+                Change::Bind(vec![leader, f], 3),
+                Change::Unbind(vec![(Modifiers::CONTROL, "Leader")])
+            ]
+        );
+        keymap.reset(user, &[(Modifiers::NONE, ","), f]);
+        assert_eq!(keymap.resolve(&[(Modifiers::NONE, ","), f]), Some(&1));
+    }
+
+    #[test]
+    fn leader_and_its_key_stay_distinct() {
+        let space = (Modifiers::NONE, "Space");
+        let tab = (Modifiers::NONE, "Tab");
+        let f = (Modifiers::NONE, "F");
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        keymap.set_leader(Modifiers::NONE, "Space");
+        keymap.bind(defaults, [(Modifiers::NONE, "Leader"), f], 1);
+        keymap.bind(defaults, [space, f], 2);
+        // The later binding takes priority.
+        assert_eq!(keymap.resolve(&[space, f]), Some(&2));
+        assert_eq!(keymap.effective(), [(&[space, f][..], &2)]);
+        // Binding again moves ahead of the other.
+        keymap.bind(defaults, [(Modifiers::NONE, "Leader"), f], 1);
+        assert_eq!(keymap.resolve(&[space, f]), Some(&1));
+        keymap.bind(defaults, [space, f], 2);
+
+        // Both survive a round trip through another leader.
+        keymap.set_leader(Modifiers::NONE, "Tab");
+        assert_eq!(keymap.resolve(&[tab, f]), Some(&1));
+        assert_eq!(keymap.resolve(&[space, f]), Some(&2));
+        keymap.set_leader(Modifiers::NONE, "Space");
+        assert_eq!(keymap.layers()[defaults].entries().count(), 2);
+        assert_eq!(keymap.resolve(&[space, f]), Some(&2));
+        keymap.set_leader(Modifiers::NONE, "Tab");
+        assert_eq!(keymap.resolve(&[tab, f]), Some(&1));
+        assert_eq!(keymap.resolve(&[space, f]), Some(&2));
+
+        // Resetting the keys removes every entry that stands for them.
+        keymap.set_leader(Modifiers::NONE, "Space");
+        keymap.reset(defaults, &[space, f]);
+        assert_eq!(keymap.resolve(&[space, f]), None);
+    }
+}
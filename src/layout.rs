@@ -0,0 +1,236 @@
+//! Keyboard layouts to translate between physical and logical keys
+//!
+//! A physical key like `Semicolon` types `;` on US QWERTY but `m` on French
+//! AZERTY. A [Layout] translates a physical key, named by its [W3C code][code],
+//! into the character it types and back again. Going from a character to a
+//! physical key adds Shift where the layout needs it. Shift stays on a key
+//! whose character only changes case, as the `lkey!` macros write it, e.g.,
+//! `Shift-a` rather than `A`.
+//!
+//! [code]: https://www.w3.org/TR/uievents-code/
+//!
+//! ```
+//! use keyseq::{Modifiers, layout::Layout};
+//!
+//! assert_eq!(Layout::UsQwerty.to_logical(Modifiers::CONTROL, "Semicolon"),
+//!            Some((Modifiers::CONTROL, ';')));
+//! assert_eq!(Layout::FrenchAzerty.to_logical(Modifiers::CONTROL, "Semicolon"),
+//!            Some((Modifiers::CONTROL, 'm')));
+//! assert_eq!(Layout::UsQwerty.to_physical(Modifiers::CONTROL, '?'),
+//!            Some((Modifiers::CONTROL | Modifiers::SHIFT, "Slash")));
+//! assert_eq!(Layout::GermanQwertz.to_physical(Modifiers::NONE, 'z'),
+//!            Some((Modifiers::NONE, "KeyY")));
+//! assert_eq!(Layout::UsQwerty.to_logical(Modifiers::SHIFT, "KeyA"),
+//!            Some((Modifiers::SHIFT, 'a')));
+//! ```
+use super::Modifiers;
+
+/// The W3C codes of the physical keys covered by each [Layout] in row order.
+pub const CODES: [&str; 49] = [
+    "Backquote",
+    "Digit1",
+    "Digit2",
+    "Digit3",
+    "Digit4",
+    "Digit5",
+    "Digit6",
+    "Digit7",
+    "Digit8",
+    "Digit9",
+    "Digit0",
+    "Minus",
+    "Equal",
+    "KeyQ",
+    "KeyW",
+    "KeyE",
+    "KeyR",
+    "KeyT",
+    "KeyY",
+    "KeyU",
+    "KeyI",
+    "KeyO",
+    "KeyP",
+    "BracketLeft",
+    "BracketRight",
+    "Backslash",
+    "KeyA",
+    "KeyS",
+    "KeyD",
+    "KeyF",
+    "KeyG",
+    "KeyH",
+    "KeyJ",
+    "KeyK",
+    "KeyL",
+    "Semicolon",
+    "Quote",
+    "IntlBackslash",
+    "KeyZ",
+    "KeyX",
+    "KeyC",
+    "KeyV",
+    "KeyB",
+    "KeyN",
+    "KeyM",
+    "Comma",
+    "Period",
+    "Slash",
+    "Space",
+];
+
+/// A keyboard layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// US QWERTY
+    UsQwerty,
+    /// UK QWERTY
+    UkQwerty,
+    /// German QWERTZ
+    GermanQwertz,
+    /// French AZERTY
+    FrenchAzerty,
+    /// US Dvorak
+    Dvorak,
+    /// Colemak
+    Colemak,
+}
+
+impl Layout {
+    /// Every layout.
+    pub const ALL: [Layout; 6] = [
+        Layout::UsQwerty,
+        Layout::UkQwerty,
+        Layout::GermanQwertz,
+        Layout::FrenchAzerty,
+        Layout::Dvorak,
+        Layout::Colemak,
+    ];
+
+    /// Return the name of the layout, e.g., "US QWERTY".
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::UsQwerty => "US QWERTY",
+            Layout::UkQwerty => "UK QWERTY",
+            Layout::GermanQwertz => "German QWERTZ",
+            Layout::FrenchAzerty => "French AZERTY",
+            Layout::Dvorak => "Dvorak",
+            Layout::Colemak => "Colemak",
+        }
+    }
+
+    /// Return the characters typed by the keys of [CODES] without and with
+    /// Shift.
+    fn table(self) -> (&'static str, &'static str) {
+        match self {
+            Layout::UsQwerty => (
+                "`1234567890-=qwertyuiop[]\\asdfghjkl;'\\zxcvbnm,./ ",
+                "~!@#$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:\"|ZXCVBNM<>? ",
+            ),
+            Layout::UkQwerty => (
+                "`1234567890-=qwertyuiop[]#asdfghjkl;'\\zxcvbnm,./ ",
+                "¬!\"£$%^&*()_+QWERTYUIOP{}~ASDFGHJKL:@|ZXCVBNM<>? ",
+            ),
+            Layout::GermanQwertz => (
+                "^1234567890ß´qwertzuiopü+#asdfghjklöä<yxcvbnm,.- ",
+                "°!\"§$%&/()=?`QWERTZUIOPÜ*'ASDFGHJKLÖÄ>YXCVBNM;:_ ",
+            ),
+            Layout::FrenchAzerty => (
+                "²&é\"'(-è_çà)=azertyuiop^$*qsdfghjklmù<wxcvbn,;:! ",
+                "²1234567890°+AZERTYUIOP¨£µQSDFGHJKLM%>WXCVBN?./§ ",
+            ),
+            Layout::Dvorak => (
+                "`1234567890[]',.pyfgcrl/=\\aoeuidhtns-\\;qjkxbmwvz ",
+                "~!@#$%^&*(){}\"<>PYFGCRL?+|AOEUIDHTNS_|:QJKXBMWVZ ",
+            ),
+            Layout::Colemak => (
+                "`1234567890-=qwfpgjluy;[]\\arstdhneio'\\zxcvbkm,./ ",
+                "~!@#$%^&*()_+QWFPGJLUY:{}|ARSTDHNEIO\"|ZXCVBKM<>? ",
+            ),
+        }
+    }
+
+    /// Return the character typed by the physical key `code` with or without
+    /// Shift.
+    pub fn char_of(self, code: &str, shift: bool) -> Option<char> {
+        let index = CODES.iter().position(|c| *c == code)?;
+        let (plain, shifted) = self.table();
+        if shift { shifted } else { plain }.chars().nth(index)
+    }
+
+    /// Return the modifiers and character of the physical chord `mods` and
+    /// `code`. Shift is consumed by the character, so `Shift-Digit1` on US
+    /// QWERTY is `!` without modifiers, unless it only changes the case, so
+    /// `Shift-KeyA` is `Shift-a`.
+    pub fn to_logical(self, mods: Modifiers, code: &str) -> Option<(Modifiers, char)> {
+        let plain = self.char_of(code, false)?;
+        let shifted = self.char_of(code, true)?;
+        if !mods.contains(Modifiers::SHIFT) || plain.to_uppercase().eq([shifted]) {
+            Some((mods, plain))
+        } else {
+            Some((mods - Modifiers::SHIFT, shifted))
+        }
+    }
+
+    /// Return the modifiers and physical key that type `ch` with the modifiers
+    /// `mods`, adding Shift if the layout needs it.
+    pub fn to_physical(self, mods: Modifiers, ch: char) -> Option<(Modifiers, &'static str)> {
+        let (plain, shifted) = self.table();
+        if let Some(index) = plain.chars().position(|c| c == ch) {
+            return Some((mods, CODES[index]));
+        }
+        shifted
+            .chars()
+            .position(|c| c == ch)
+            .map(|index| (mods | Modifiers::SHIFT, CODES[index]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_cover_every_code() {
+        for layout in Layout::ALL {
+            let (plain, shifted) = layout.table();
+            assert_eq!(plain.chars().count(), CODES.len(), "{}", layout.name());
+            assert_eq!(shifted.chars().count(), CODES.len(), "{}", layout.name());
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for layout in Layout::ALL {
+            for code in CODES {
+                for mods in [Modifiers::NONE, Modifiers::SHIFT] {
+                    let (logical_mods, ch) = layout.to_logical(mods, code).unwrap();
+                    let (physical_mods, physical_code) =
+                        layout.to_physical(logical_mods, ch).unwrap();
+                    assert_eq!(physical_mods, mods, "{} {code}", layout.name());
+                    // Backslash and IntlBackslash type the same characters on
+                    // some layouts and map back to the first.
+                    let first = CODES
+                        .iter()
+                        .find(|c| layout.to_logical(mods, c) == Some((logical_mods, ch)));
+                    assert_eq!(Some(&physical_code), first, "{} {code}", layout.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(
+            Layout::Dvorak.to_logical(Modifiers::SHIFT, "KeyS"),
+            Some((Modifiers::SHIFT, 'o'))
+        );
+        assert_eq!(
+            Layout::GermanQwertz.to_logical(Modifiers::SHIFT, "Semicolon"),
+            Some((Modifiers::SHIFT, 'ö'))
+        );
+        assert_eq!(
+            Layout::Colemak.to_physical(Modifiers::CONTROL, 'S'),
+            Some((Modifiers::CONTROL | Modifiers::SHIFT, "KeyD"))
+        );
+    }
+}
@@ -41,15 +41,62 @@ pub mod poor {
 //! These particular representations are impractical since one would need to
 //! interrogate untyped bitflags and a string. The real use case requires
 //! features.
+//!
+//! ## Optional Modifiers
+//!
+//! A modifier followed by a question mark is optional. In a pattern it expands
+//! to a match-or of each combination. In an expression it is a compile error.
+//!
+//! ```
+//! use keyseq::poor::pkey;
+//! assert!(matches!((1, "Equal"), pkey! { Ctrl-Shift?-Equal }));
+//! assert!(matches!((5, "Equal"), pkey! { Ctrl-Shift?-Equal }));
+//! assert!(!matches!((0, "Equal"), pkey! { Ctrl-Shift?-Equal }));
+//! ```
+//!
+//! ```compile_fail
+//! use keyseq::poor::pkey;
+//! let _ = pkey! { Ctrl-Shift?-Equal };
+//! ```
+//!
+//! ## Key Ranges
+//!
+//! A parenthesized range of digits, function keys, or numpad keys also expands
+//! to a match-or.
+//!
+//! ```
+//! use keyseq::poor::pkey;
+//! assert!(matches!((2, "Key7"), pkey! { Alt-(1..=9) }));
+//! assert!(matches!((0, "F12"),  pkey! { F(1..=12) }));
+//! assert!(!matches!((0, "F12"), pkey! { F(1..12) }));
+//! assert!(matches!((0, "Numpad0"), pkey! { Numpad(0..=9) }));
+//! ```
+//!
+//! ## Runtime Chords
+//!
+//! Outside of a pattern the match-or is a bit-or, so use the `pchord!` macro to
+//! keep optional modifiers at runtime.
+//!
+//! ```
+//! use keyseq::{Chord, Modifiers, _keyseq, poor::pchord};
+//! let zoom = pchord! { Ctrl-Shift?-Equal };
+//! assert_eq!(zoom, Chord::new(Modifiers::CONTROL, "Equal").with_optional(Modifiers::SHIFT));
+//! assert!(zoom.matches(Modifiers::CONTROL | Modifiers::SHIFT, &"Equal"));
+//! ```
     pub use keyseq_macros::{poor_lkey as lkey,
                             poor_lkeyseq as lkeyseq,
                             poor_pkey as pkey,
-                            poor_pkeyseq as pkeyseq};
+                            poor_pkeyseq as pkeyseq,
+                            poor_lchord as lchord,
+                            poor_lchordseq as lchordseq,
+                            poor_pchord as pchord,
+                            poor_pchordseq as pchordseq,
+                            poor_pcombo as pcombo};
 }
 
 /// A bit flag that stores the modifier keys--control, Alt, Shift, and
 /// Super--in a byte.
-#[derive(Clone, Copy, PartialOrd, PartialEq, Eq, Hash, Ord)]
+#[derive(Clone, Copy, Default, PartialOrd, PartialEq, Eq, Hash, Ord)]
 #[cfg_attr(feature = "bevy", derive(bevy_reflect::Reflect))]
 pub struct Modifiers(pub u8);
 
@@ -68,9 +115,28 @@ bitflags! {
     }
 }
 
+pub mod bindings;
+#[cfg(any(feature = "poor", feature = "bevy", feature = "winit"))]
+pub use keyseq_macros::keymap;
+pub mod capture;
+pub mod cheatsheet;
+pub mod chord;
+pub use chord::{Chord, Press};
+pub mod combo;
+pub mod conflict;
+pub mod display;
+pub mod keymap;
+pub mod layout;
+pub mod modal;
+pub mod modifier_chord;
+pub mod notation;
+pub mod press;
+pub mod release;
+pub mod repeat;
+
 #[doc(hidden)]
 pub mod _keyseq {
-    pub use super::Modifiers;
+    pub use super::{bindings, conflict, Chord, Modifiers, Press};
 }
 
 impl fmt::Debug for Modifiers {
@@ -127,6 +193,112 @@ impl From<u8> for Modifiers {
     }
 }
 
+/// Return the number of `$value` if it is a `$key` digit, function, or numpad
+/// key. Bevy and winit share it for their `key_index()`.
+#[cfg(any(feature = "bevy", feature = "winit"))]
+macro_rules! key_index {
+    ($key:ident, $value:expr) => {
+        match $value {
+            $key::Digit0 | $key::Numpad0 => Some(0),
+            $key::Digit1 | $key::Numpad1 | $key::F1 => Some(1),
+            $key::Digit2 | $key::Numpad2 | $key::F2 => Some(2),
+            $key::Digit3 | $key::Numpad3 | $key::F3 => Some(3),
+            $key::Digit4 | $key::Numpad4 | $key::F4 => Some(4),
+            $key::Digit5 | $key::Numpad5 | $key::F5 => Some(5),
+            $key::Digit6 | $key::Numpad6 | $key::F6 => Some(6),
+            $key::Digit7 | $key::Numpad7 | $key::F7 => Some(7),
+            $key::Digit8 | $key::Numpad8 | $key::F8 => Some(8),
+            $key::Digit9 | $key::Numpad9 | $key::F9 => Some(9),
+            $key::F10 => Some(10),
+            $key::F11 => Some(11),
+            $key::F12 => Some(12),
+            $key::F13 => Some(13),
+            $key::F14 => Some(14),
+            $key::F15 => Some(15),
+            $key::F16 => Some(16),
+            $key::F17 => Some(17),
+            $key::F18 => Some(18),
+            $key::F19 => Some(19),
+            $key::F20 => Some(20),
+            $key::F21 => Some(21),
+            $key::F22 => Some(22),
+            $key::F23 => Some(23),
+            $key::F24 => Some(24),
+            $key::F25 => Some(25),
+            $key::F26 => Some(26),
+            $key::F27 => Some(27),
+            $key::F28 => Some(28),
+            $key::F29 => Some(29),
+            $key::F30 => Some(30),
+            $key::F31 => Some(31),
+            $key::F32 => Some(32),
+            $key::F33 => Some(33),
+            $key::F34 => Some(34),
+            $key::F35 => Some(35),
+            _ => None,
+        }
+    };
+}
+
+/// Build an array of the `$key` physical keys of [layout::CODES] in the same
+/// order. Bevy and winit name their `KeyCode` variants after the W3C codes.
+#[cfg(any(feature = "bevy", feature = "winit"))]
+macro_rules! layout_keys {
+    ($key:ident) => {
+        [
+            $key::Backquote,
+            $key::Digit1,
+            $key::Digit2,
+            $key::Digit3,
+            $key::Digit4,
+            $key::Digit5,
+            $key::Digit6,
+            $key::Digit7,
+            $key::Digit8,
+            $key::Digit9,
+            $key::Digit0,
+            $key::Minus,
+            $key::Equal,
+            $key::KeyQ,
+            $key::KeyW,
+            $key::KeyE,
+            $key::KeyR,
+            $key::KeyT,
+            $key::KeyY,
+            $key::KeyU,
+            $key::KeyI,
+            $key::KeyO,
+            $key::KeyP,
+            $key::BracketLeft,
+            $key::BracketRight,
+            $key::Backslash,
+            $key::KeyA,
+            $key::KeyS,
+            $key::KeyD,
+            $key::KeyF,
+            $key::KeyG,
+            $key::KeyH,
+            $key::KeyJ,
+            $key::KeyK,
+            $key::KeyL,
+            $key::Semicolon,
+            $key::Quote,
+            $key::IntlBackslash,
+            $key::KeyZ,
+            $key::KeyX,
+            $key::KeyC,
+            $key::KeyV,
+            $key::KeyB,
+            $key::KeyN,
+            $key::KeyM,
+            $key::Comma,
+            $key::Period,
+            $key::Slash,
+            $key::Space,
+        ]
+    };
+}
+
 #[cfg(feature = "winit")]
 pub mod winit;
 
@@ -150,6 +322,59 @@ mod tests {
         assert_eq!(format!("{}", mods), "Ctrl-Alt-Shift");
     }
 
+    #[cfg(feature = "poor")]
+    #[test]
+    fn optional_modifiers() {
+        let matches = |chord: (u8, &str)| matches!(chord, poor::pkey! { Ctrl-Shift?-Super?-Equal });
+        assert!(matches((1, "Equal")));
+        assert!(matches((5, "Equal")));
+        assert!(matches((9, "Equal")));
+        assert!(matches((13, "Equal")));
+        assert!(!matches((0, "Equal")));
+        assert!(!matches((3, "Equal")));
+    }
+
+    #[cfg(feature = "poor")]
+    #[test]
+    fn optional_chord_seq() {
+        let seq = poor::pchordseq! { Ctrl-X Alt?-S };
+        assert_eq!(
+            seq[1],
+            Chord::new(Modifiers::NONE, "S").with_optional(Modifiers::ALT)
+        );
+        assert!(chord::matches_seq(
+            &seq,
+            &[(Modifiers::CONTROL, "X"), (Modifiers::ALT, "S")]
+        ));
+        assert!(!chord::matches_seq(
+            &seq,
+            &[(Modifiers::CONTROL, "X"), (Modifiers::SHIFT, "S")]
+        ));
+    }
+
+    #[cfg(feature = "poor")]
+    #[test]
+    fn press_qualifiers() {
+        let seq = poor::pchordseq! { Tap(Ctrl) Hold(Space) Release(Alt-A) Ctrl-Shift };
+        assert_eq!(seq[0], Chord::from_modifiers(Modifiers::CONTROL));
+        assert_eq!(
+            seq[1],
+            Chord::new(Modifiers::NONE, "Space").with_press(Press::Hold(Press::DEFAULT_HOLD))
+        );
+        assert_eq!(
+            seq[2],
+            Chord::new(Modifiers::ALT, "A").with_press(Press::Release)
+        );
+        assert_eq!(
+            seq[3],
+            Chord::from_modifiers(Modifiers::CONTROL | Modifiers::SHIFT)
+        );
+        assert_eq!(
+            poor::pchord! { Space Space },
+            Chord::new(Modifiers::NONE, "Space").with_press(Press::DoubleTap)
+        );
+    }
+
     #[cfg(all(feature = "poor", feature = "permit-plus"))]
     #[test]
     fn permit_plus() {
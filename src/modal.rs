@@ -0,0 +1,350 @@
+//! Modal keymaps like vim's normal, insert, and visual modes
+//!
+//! Each mode owns its own key sequence bindings. A binding may fire an action,
+//! switch to another mode, or both. In a passthrough mode, keys that match no
+//! binding are passed through, e.g., to text input.
+//!
+//! ```
+//! use keyseq::{Modifiers, modal::{ModalEvent, ModalKeymap}};
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! enum Mode { Normal, Insert }
+//!
+//! let mut keymap = ModalKeymap::new(Mode::Normal);
+//! keymap.bind(Mode::Normal, [(Modifiers::NONE, "D"), (Modifiers::NONE, "D")], "delete-line");
+//! keymap.switch(Mode::Normal, [(Modifiers::NONE, "I")], Mode::Insert);
+//! keymap.switch(Mode::Insert, [(Modifiers::NONE, "Escape")], Mode::Normal);
+//! keymap.set_passthrough(Mode::Insert, true);
+//!
+//! for key in ["D", "D", "I", "H", "Escape"] {
+//!     keymap.press(Modifiers::NONE, key);
+//! }
+//! assert_eq!(keymap.drain().collect::<Vec<_>>(), [
+//!     ModalEvent::Action("delete-line"),
+//!     ModalEvent::ModeChanged { from: Mode::Normal, to: Mode::Insert },
+//!     ModalEvent::Passthrough(Modifiers::NONE, "H"),
+//!     ModalEvent::ModeChanged { from: Mode::Insert, to: Mode::Normal },
+//! ]);
+//! ```
+use super::{keymap::KeySeq, Modifiers};
+use std::collections::VecDeque;
+
+/// The output of a [ModalKeymap].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModalEvent<M, K, A> {
+    /// A binding fired this action.
+    Action(A),
+    /// A binding switched modes.
+    ModeChanged {
+        /// The previous mode.
+        from: M,
+        /// The new mode.
+        to: M,
+    },
+    /// An unmatched key in a passthrough mode.
+    Passthrough(Modifiers, K),
+}
+
+#[derive(Clone, Debug)]
+struct ModeBinding<M, K, A> {
+    keys: KeySeq<K>,
+    action: Option<A>,
+    switch_to: Option<M>,
+}
+
+#[derive(Clone, Debug)]
+struct Mode<M, K, A> {
+    mode: M,
+    bindings: Vec<ModeBinding<M, K, A>>,
+    passthrough: bool,
+}
+
+/// A keymap whose bindings depend on the active mode.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct ModalKeymap<M, K, A> {
+    modes: Vec<Mode<M, K, A>>,
+    current: M,
+    /// The keys of a sequence in progress.
+    pending: KeySeq<K>,
+    events: VecDeque<ModalEvent<M, K, A>>,
+}
+
+impl<M: Clone + PartialEq, K: Clone + PartialEq, A: Clone> ModalKeymap<M, K, A> {
+    /// Create a keymap whose active mode is `initial`.
+    pub fn new(initial: M) -> Self {
+        ModalKeymap {
+            modes: vec![],
+            current: initial,
+            pending: vec![],
+            events: VecDeque::new(),
+        }
+    }
+
+    fn mode_mut(&mut self, mode: M) -> &mut Mode<M, K, A> {
+        match self.modes.iter().position(|m| m.mode == mode) {
+            Some(index) => &mut self.modes[index],
+            None => {
+                self.modes.push(Mode {
+                    mode,
+                    bindings: vec![],
+                    passthrough: false,
+                });
+                self.modes.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn add(
+        &mut self,
+        mode: M,
+        keys: impl IntoIterator<Item = (Modifiers, K)>,
+        action: Option<A>,
+        switch_to: Option<M>,
+    ) {
+        let keys: KeySeq<K> = keys.into_iter().collect();
+        let bindings = &mut self.mode_mut(mode).bindings;
+        bindings.retain(|binding| binding.keys != keys);
+        bindings.push(ModeBinding {
+            keys,
+            action,
+            switch_to,
+        });
+    }
+
+    /// Bind `keys` to `action` in `mode`.
+    pub fn bind(&mut self, mode: M, keys: impl IntoIterator<Item = (Modifiers, K)>, action: A) {
+        self.add(mode, keys, Some(action), None);
+    }
+
+    /// Bind `keys` in `mode` to switch to mode `to`.
+    pub fn switch(&mut self, mode: M, keys: impl IntoIterator<Item = (Modifiers, K)>, to: M) {
+        self.add(mode, keys, None, Some(to));
+    }
+
+    /// Bind `keys` in `mode` to fire `action` and then switch to mode `to`.
+    pub fn bind_and_switch(
+        &mut self,
+        mode: M,
+        keys: impl IntoIterator<Item = (Modifiers, K)>,
+        action: A,
+        to: M,
+    ) {
+        self.add(mode, keys, Some(action), Some(to));
+    }
+
+    /// Pass unmatched keys through in `mode` if `passthrough` is true.
+    pub fn set_passthrough(&mut self, mode: M, passthrough: bool) {
+        self.mode_mut(mode).passthrough = passthrough;
+    }
+
+    /// Return the active mode.
+    pub fn current(&self) -> &M {
+        &self.current
+    }
+
+    /// Make `mode` active without a [ModalEvent::ModeChanged] event, e.g., to
+    /// follow an outside state. Any sequence in progress is dropped.
+    pub fn set_mode(&mut self, mode: M) {
+        self.pending.clear();
+        self.current = mode;
+    }
+
+    /// Return the keys of the sequence in progress.
+    pub fn pending(&self) -> &[(Modifiers, K)] {
+        &self.pending
+    }
+
+    /// Return true if a sequence is in progress.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Record a non-modifier key press with the modifiers held.
+    pub fn press(&mut self, mods: Modifiers, key: K) {
+        self.pending.push((mods, key));
+        loop {
+            let Some(mode) = self.modes.iter().find(|m| m.mode == self.current) else {
+                self.reject_pending(false);
+                return;
+            };
+            let exact = mode
+                .bindings
+                .iter()
+                .position(|binding| binding.keys == self.pending);
+            let longer = mode.bindings.iter().any(|binding| {
+                binding.keys.len() > self.pending.len() && binding.keys.starts_with(&self.pending)
+            });
+            match (exact, longer) {
+                (Some(index), false) => {
+                    let binding = mode.bindings[index].clone();
+                    self.fire(binding);
+                    return;
+                }
+                // Wait for more keys; see flush() for the ambiguous case.
+                (_, true) => return,
+                (None, false) if self.pending.len() > 1 => {
+                    // Resolve the keys before the last, which may start a
+                    // sequence of its own.
+                    let last = self.pending.pop().unwrap();
+                    self.flush();
+                    self.pending.push(last);
+                }
+                (None, false) => {
+                    let passthrough = mode.passthrough;
+                    self.reject_pending(passthrough);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Resolve a sequence in progress, e.g., after a timeout. The longest
+    /// bound start of it fires, even though a longer binding was possible,
+    /// and so on for the keys after it. Keys that start nothing bound are
+    /// rejected.
+    pub fn flush(&mut self) {
+        let mut rest = std::mem::take(&mut self.pending);
+        while !rest.is_empty() {
+            let mode = self.modes.iter().find(|m| m.mode == self.current);
+            let found = mode.and_then(|mode| {
+                (1..=rest.len()).rev().find_map(|n| {
+                    mode.bindings
+                        .iter()
+                        .find(|binding| binding.keys[..] == rest[..n])
+                        .map(|binding| (n, binding.clone()))
+                })
+            });
+            match found {
+                Some((n, binding)) => {
+                    rest.drain(..n);
+                    self.fire(binding);
+                }
+                None => {
+                    let passthrough = mode.is_some_and(|m| m.passthrough);
+                    self.pending.push(rest.remove(0));
+                    self.reject_pending(passthrough);
+                }
+            }
+        }
+    }
+
+    /// Drain the events.
+    pub fn drain(&mut self) -> impl Iterator<Item = ModalEvent<M, K, A>> + '_ {
+        self.events.drain(..)
+    }
+
+    fn fire(&mut self, binding: ModeBinding<M, K, A>) {
+        self.pending.clear();
+        if let Some(action) = binding.action {
+            self.events.push_back(ModalEvent::Action(action));
+        }
+        if let Some(to) = binding.switch_to {
+            let from = std::mem::replace(&mut self.current, to.clone());
+            self.events.push_back(ModalEvent::ModeChanged { from, to });
+        }
+    }
+
+    fn reject_pending(&mut self, passthrough: bool) {
+        let pending = self.pending.drain(..);
+        if passthrough {
+            self.events
+                .extend(pending.map(|(mods, key)| ModalEvent::Passthrough(mods, key)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Mode {
+        Normal,
+        Visual,
+    }
+
+    fn key(k: &'static str) -> [(Modifiers, &'static str); 1] {
+        [(Modifiers::NONE, k)]
+    }
+
+    #[test]
+    fn unmatched_keys_dropped_outside_passthrough() {
+        let mut keymap = ModalKeymap::new(Mode::Normal);
+        keymap.bind(Mode::Normal, key("X"), 1);
+        keymap.press(Modifiers::NONE, "Q");
+        keymap.press(Modifiers::NONE, "X");
+        assert_eq!(keymap.drain().collect::<Vec<_>>(), [ModalEvent::Action(1)]);
+    }
+
+    #[test]
+    fn bindings_are_per_mode() {
+        let mut keymap = ModalKeymap::new(Mode::Normal);
+        keymap.switch(Mode::Normal, key("V"), Mode::Visual);
+        keymap.bind_and_switch(Mode::Visual, key("Y"), "yank", Mode::Normal);
+        keymap.press(Modifiers::NONE, "Y");
+        assert_eq!(keymap.drain().count(), 0);
+        keymap.press(Modifiers::NONE, "V");
+        keymap.press(Modifiers::NONE, "Y");
+        assert_eq!(
+            keymap.drain().collect::<Vec<_>>(),
+            [
+                ModalEvent::ModeChanged {
+                    from: Mode::Normal,
+                    to: Mode::Visual
+                },
+                ModalEvent::Action("yank"),
+                ModalEvent::ModeChanged {
+                    from: Mode::Visual,
+                    to: Mode::Normal
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_sequence_resolves_prefix() {
+        let mut keymap = ModalKeymap::new(Mode::Normal);
+        keymap.bind(
+            Mode::Normal,
+            [(Modifiers::NONE, "G"), (Modifiers::NONE, "G")],
+            1,
+        );
+        keymap.bind(Mode::Normal, key("D"), 2);
+        keymap.bind(Mode::Normal, key("G"), 3);
+        keymap.press(Modifiers::NONE, "G");
+        assert!(keymap.is_pending());
+        keymap.press(Modifiers::NONE, "D");
+        assert_eq!(
+            keymap.drain().collect::<Vec<_>>(),
+            [ModalEvent::Action(3), ModalEvent::Action(2)]
+        );
+        keymap.press(Modifiers::NONE, "G");
+        keymap.flush();
+        assert_eq!(keymap.drain().collect::<Vec<_>>(), [ModalEvent::Action(3)]);
+    }
+
+    #[test]
+    fn broken_sequence_fires_longest_bound_start() {
+        let mut keymap = ModalKeymap::new(Mode::Normal);
+        keymap.bind(Mode::Normal, key("A"), 1);
+        keymap.bind(
+            Mode::Normal,
+            [
+                (Modifiers::NONE, "A"),
+                (Modifiers::NONE, "B"),
+                (Modifiers::NONE, "C"),
+            ],
+            2,
+        );
+        keymap.bind(Mode::Normal, key("X"), 3);
+        for k in ["A", "B", "X"] {
+            keymap.press(Modifiers::NONE, k);
+        }
+        assert_eq!(
+            keymap.drain().collect::<Vec<_>>(),
+            [ModalEvent::Action(1), ModalEvent::Action(3)]
+        );
+        assert!(!keymap.is_pending());
+    }
+}
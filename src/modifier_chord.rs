@@ -0,0 +1,98 @@
+//! Recognize chords of only modifiers
+//!
+//! Pressing and releasing Alt by itself often opens a menu, and pressing
+//! Ctrl-Shift by itself may switch keyboard layouts. A
+//! [ModifierChordRecognizer] reports such a chord when a modifier is released,
+//! but only if no other key was pressed in between.
+//!
+//! ```
+//! use keyseq::{Modifiers, _keyseq, modifier_chord::ModifierChordRecognizer, poor::pchord};
+//! let mut recognizer = ModifierChordRecognizer::default();
+//! recognizer.press_modifier(Modifiers::CONTROL);
+//! recognizer.press_modifier(Modifiers::SHIFT);
+//! recognizer.release_modifier(Modifiers::SHIFT);
+//! recognizer.release_modifier(Modifiers::CONTROL);
+//! let chords: Vec<Modifiers> = recognizer.drain().collect();
+//! assert_eq!(chords, [Modifiers::CONTROL | Modifiers::SHIFT]);
+//! assert!(pchord! { Ctrl-Shift }.matches_modifiers(chords[0]));
+//!
+//! // Ctrl-C is not a modifier chord.
+//! recognizer.press_modifier(Modifiers::CONTROL);
+//! recognizer.press_key();
+//! recognizer.release_modifier(Modifiers::CONTROL);
+//! assert_eq!(recognizer.drain().count(), 0);
+//!
+//! // Releasing one Shift key while the other is held keeps Shift held.
+//! recognizer.press_modifier(Modifiers::SHIFT);
+//! recognizer.press_modifier(Modifiers::SHIFT);
+//! recognizer.release_modifier(Modifiers::SHIFT);
+//! assert_eq!(recognizer.held(), Modifiers::SHIFT);
+//! ```
+use super::Modifiers;
+use std::collections::VecDeque;
+
+/// Recognizes chords of only modifiers, which fire on release.
+#[derive(Clone, Debug, Default)]
+pub struct ModifierChordRecognizer {
+    held: Modifiers,
+    /// How many keys of each modifier are down, e.g., both Shift keys.
+    counts: [u8; 4],
+    /// The modifiers held at once since the first one was pressed.
+    peak: Modifiers,
+    /// A key was pressed or the chord already fired.
+    spoiled: bool,
+    events: VecDeque<Modifiers>,
+}
+
+impl ModifierChordRecognizer {
+    /// Record a modifier key press.
+    pub fn press_modifier(&mut self, modifier: Modifiers) {
+        if self.held.is_empty() {
+            self.peak = Modifiers::NONE;
+            self.spoiled = false;
+        }
+        for (bit, count) in self.counts.iter_mut().enumerate() {
+            if modifier.contains(Modifiers(1 << bit)) {
+                *count = count.saturating_add(1);
+            }
+        }
+        self.held |= modifier;
+        self.peak |= modifier;
+    }
+
+    /// Record a modifier key release. If no other key was pressed, the
+    /// modifiers that were held fire as a chord.
+    pub fn release_modifier(&mut self, modifier: Modifiers) {
+        if !self.held.intersects(modifier) {
+            return;
+        }
+        if !self.spoiled {
+            self.events.push_back(self.peak);
+            // Only the first release fires.
+            self.spoiled = true;
+        }
+        for (bit, count) in self.counts.iter_mut().enumerate() {
+            if modifier.contains(Modifiers(1 << bit)) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    self.held.remove(Modifiers(1 << bit));
+                }
+            }
+        }
+    }
+
+    /// Record a non-modifier key press.
+    pub fn press_key(&mut self) {
+        self.spoiled = true;
+    }
+
+    /// Return the modifiers currently held.
+    pub fn held(&self) -> Modifiers {
+        self.held
+    }
+
+    /// Drain the modifier chords that fired.
+    pub fn drain(&mut self) -> impl Iterator<Item = Modifiers> + '_ {
+        self.events.drain(..)
+    }
+}
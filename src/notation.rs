@@ -0,0 +1,486 @@
+//! Read and write key sequences as text at runtime
+//!
+//! The macros read keyseq's notation, e.g., `Ctrl-X Ctrl-S`, at compile time.
+//! Configuration files need the same at runtime. Keys are named by strings:
+//! letters are uppercase, other printable keys are their character, and other
+//! keys have a name like `F5`, `Enter`, or `ArrowUp`, the same names the
+//! logical key macros accept, see [is_key_name()]. Shift is always explicit,
+//! so `Shift-A` is a capital A.
+//!
+//! ```
+//! use keyseq::{Modifiers, notation::{format_keyseq, parse_keyseq}};
+//! let seq = parse_keyseq("Ctrl-X Ctrl-s").unwrap();
+//! assert_eq!(seq, [(Modifiers::CONTROL, "X".to_string()), (Modifiers::CONTROL, "S".to_string())]);
+//! assert_eq!(format_keyseq(&seq), "Ctrl-X Ctrl-S");
+//! assert!(parse_keyseq("Ctrl-").is_err());
+//! assert!(parse_keyseq("Ctrl-Sapce").is_err());
+//! ```
+//!
+//! Other editors' notations are read and written by the submodules.
+//!
+//! A binding file has one binding per line, its key sequence and action
+//! separated by ` = `. A line in brackets starts a group, and lines starting
+//! with `#` are comments. Optional modifiers like `Shift?` bind every
+//! combination. See [parse_bindings()].
+//!
+//! ```text
+//! # My editor
+//! [File]
+//! Ctrl-S = save
+//! Ctrl-X Ctrl-S = save
+//! Ctrl-Shift?-= = zoom-in
+//! ```
+use super::{
+    display::Style,
+    keymap::{KeySeq, Leader},
+    Chord, Modifiers,
+};
+use std::fmt;
+
+pub mod electron;
+pub mod emacs;
+pub mod vim;
+pub mod vscode;
+
+/// The named logical keys, shared with the macros.
+const NAMED_KEYS: &[&str] = &keyseq_macros::named_keys!();
+
+/// An error reading a key sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub message: String,
+    /// The part of the input that could not be read.
+    pub token: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(message: impl Into<String>, token: impl Into<String>) -> Self {
+        ParseError {
+            message: message.into(),
+            token: token.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in `{}`", self.message, self.token)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A notation for key sequences.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// keyseq's notation, e.g., `Ctrl-X Ctrl-S`.
+    #[default]
+    Keyseq,
+    /// Emacs notation, e.g., `C-x C-s`, see [emacs].
+    Emacs,
+    /// Vim notation, e.g., `<C-x><C-s>`, see [vim].
+    Vim,
+    /// VS Code notation, e.g., `ctrl+x ctrl+s`, see [vscode].
+    VsCode,
+    /// macOS glyphs, e.g., `⌃X ⌃S`, which can only be written.
+    MacGlyphs,
+}
+
+impl Notation {
+    /// Every notation.
+    pub const ALL: [Notation; 5] = [
+        Notation::Keyseq,
+        Notation::Emacs,
+        Notation::Vim,
+        Notation::VsCode,
+        Notation::MacGlyphs,
+    ];
+
+    /// Return the name of the notation, e.g., "vscode".
+    pub fn name(self) -> &'static str {
+        match self {
+            Notation::Keyseq => "keyseq",
+            Notation::Emacs => "emacs",
+            Notation::Vim => "vim",
+            Notation::VsCode => "vscode",
+            Notation::MacGlyphs => "mac",
+        }
+    }
+
+    /// Return the notation named `name`.
+    pub fn from_name(name: &str) -> Option<Notation> {
+        Notation::ALL.into_iter().find(|n| n.name() == name)
+    }
+
+    /// Read a chord sequence, which may have optional modifiers in keyseq's
+    /// notation.
+    pub fn parse_chords(self, text: &str) -> Result<Vec<Chord<String>>, ParseError> {
+        match self {
+            Notation::Keyseq => parse_chordseq(text),
+            _ => Ok(self.parse(text)?.into_iter().map(Chord::from).collect()),
+        }
+    }
+
+    /// Read a key sequence.
+    pub fn parse(self, text: &str) -> Result<KeySeq<String>, ParseError> {
+        match self {
+            Notation::Keyseq => parse_keyseq(text),
+            Notation::Emacs => emacs::parse(text),
+            Notation::Vim => vim::parse(text),
+            Notation::VsCode => vscode::parse(text),
+            Notation::MacGlyphs => Err(ParseError::new("macOS glyphs cannot be read", text)),
+        }
+    }
+
+    /// Write a key sequence.
+    pub fn format<S: AsRef<str>>(self, seq: &[(Modifiers, S)]) -> String {
+        match self {
+            Notation::Keyseq => format_keyseq(seq),
+            Notation::Emacs => emacs::format(seq),
+            Notation::Vim => vim::format(seq),
+            Notation::VsCode => vscode::format(seq),
+            Notation::MacGlyphs => Style::MacGlyphs.format_keyseq(seq),
+        }
+    }
+}
+
+/// A binding read from a binding file. A line with optional modifiers yields
+/// a binding for each combination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileBinding {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The group of the binding or "" before any group.
+    pub group: String,
+    /// The key sequence.
+    pub keys: KeySeq<String>,
+    /// The action.
+    pub action: String,
+}
+
+/// Read a binding file whose key sequences are in `notation`. Every error is
+/// returned with its line number.
+///
+/// ```
+/// use keyseq::notation::{parse_bindings, Notation};
+/// let bindings = parse_bindings("[File]\nC-x C-s = save\n", Notation::Emacs).unwrap();
+/// assert_eq!(bindings[0].group, "File");
+/// assert_eq!(bindings[0].action, "save");
+/// let errors = parse_bindings("C-x = cut\nC-q\n", Notation::Emacs).unwrap_err();
+/// assert_eq!(errors[0].0, 2);
+/// ```
+pub fn parse_bindings(
+    text: &str,
+    notation: Notation,
+) -> Result<Vec<FileBinding>, Vec<(usize, ParseError)>> {
+    let mut bindings = vec![];
+    let mut errors = vec![];
+    let mut group = String::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            group = name.trim().to_string();
+            continue;
+        }
+        // The keys may contain `=`, so split at the last separator.
+        let Some((keys, action)) = trimmed.rsplit_once(" = ") else {
+            errors.push((
+                line_number,
+                ParseError::new("expected `keys = action`", trimmed),
+            ));
+            continue;
+        };
+        match notation.parse_chords(keys.trim()) {
+            Ok(chords) if chords.is_empty() => {
+                errors.push((line_number, ParseError::new("missing keys", trimmed)))
+            }
+            Ok(chords) => {
+                bindings.extend(
+                    expand_optional(&chords)
+                        .into_iter()
+                        .map(|keys| FileBinding {
+                            line: line_number,
+                            group: group.clone(),
+                            keys,
+                            action: action.trim().to_string(),
+                        }),
+                )
+            }
+            Err(error) => errors.push((line_number, error)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(bindings)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Return every key sequence `chords` match, first without their optional
+/// modifiers.
+fn expand_optional(chords: &[Chord<String>]) -> Vec<KeySeq<String>> {
+    let mut seqs: Vec<KeySeq<String>> = vec![vec![]];
+    for chord in chords {
+        let Some(ref key) = chord.key else {
+            continue;
+        };
+        let optional = chord.optional.bits();
+        seqs = seqs
+            .iter()
+            .flat_map(|seq| {
+                (0..=optional)
+                    .filter(move |bits| bits & !optional == 0)
+                    .map(move |bits| {
+                        let mut seq = seq.clone();
+                        seq.push((chord.mods | Modifiers(bits), key.clone()));
+                        seq
+                    })
+            })
+            .collect();
+    }
+    seqs
+}
+
+/// Return true if `name` is a key in keyseq's notation: a single character, a
+/// named logical key like `Enter` or `F5`, or the [Leader] placeholder.
+///
+/// ```
+/// use keyseq::notation::is_key_name;
+/// assert!(is_key_name("A"));
+/// assert!(is_key_name("ArrowUp"));
+/// assert!(!is_key_name("Sapce"));
+/// ```
+pub fn is_key_name(name: &str) -> bool {
+    key_char(name).is_some() || NAMED_KEYS.contains(&name) || name == <&str as Leader>::leader()
+}
+
+/// Return the key name of a single character, uppercasing letters.
+pub(crate) fn char_key(ch: char) -> String {
+    ch.to_uppercase().collect()
+}
+
+/// Return the character of a key name if it is a single character.
+pub(crate) fn key_char(key: &str) -> Option<char> {
+    let mut chars = key.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+/// Split a chord like `ctrl+shift+p` into its modifier names and key. The
+/// key may be the plus sign itself, e.g., `ctrl++`.
+pub(crate) fn split_plus(token: &str) -> Result<(Vec<&str>, &str), ParseError> {
+    let (head, key) = if token == "+" {
+        ("", "+")
+    } else if let Some(head) = token.strip_suffix("++") {
+        (head, "+")
+    } else {
+        token.rsplit_once('+').unwrap_or(("", token))
+    };
+    if key.is_empty() {
+        return Err(ParseError::new("missing key", token));
+    }
+    let mods: Vec<&str> = if head.is_empty() {
+        vec![]
+    } else {
+        head.split('+').collect()
+    };
+    if mods.iter().any(|m| m.is_empty()) {
+        return Err(ParseError::new("missing modifier", token));
+    }
+    Ok((mods, key))
+}
+
+/// Read a key sequence in keyseq's notation, e.g., `Ctrl-X Ctrl-S`.
+pub fn parse_keyseq(text: &str) -> Result<KeySeq<String>, ParseError> {
+    text.split_whitespace().map(parse_chord).collect()
+}
+
+/// Read a chord in keyseq's notation, e.g., `Ctrl-Alt-Delete` or `Ctrl--`.
+/// Optional modifiers are an error; see [parse_chordseq()].
+pub fn parse_chord(token: &str) -> Result<(Modifiers, String), ParseError> {
+    let chord = read_chord(token)?;
+    if !chord.optional.is_empty() {
+        return Err(ParseError::new(
+            "optional modifiers need a chord, see `parse_chordseq()`",
+            token,
+        ));
+    }
+    Ok((chord.mods, chord.key.unwrap_or_default()))
+}
+
+/// Read a chord sequence in keyseq's notation, which may have optional
+/// modifiers like the `pchordseq!` macros.
+///
+/// ```
+/// use keyseq::{Chord, Modifiers, notation::parse_chordseq};
+/// assert_eq!(parse_chordseq("Ctrl-Shift?-=").unwrap(),
+///            [Chord::new(Modifiers::CONTROL, "=".to_string()).with_optional(Modifiers::SHIFT)]);
+/// ```
+pub fn parse_chordseq(text: &str) -> Result<Vec<Chord<String>>, ParseError> {
+    text.split_whitespace().map(read_chord).collect()
+}
+
+fn read_chord(token: &str) -> Result<Chord<String>, ParseError> {
+    let mut mods = Modifiers::NONE;
+    let mut optional = Modifiers::NONE;
+    let mut rest = token;
+    while let Some((name, tail)) = rest.split_once('-') {
+        if tail.is_empty() && name.is_empty() {
+            // The key is the minus sign.
+            break;
+        }
+        let (name, is_optional) = match name.strip_suffix('?') {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        let modifier = match name {
+            "Ctrl" | "Control" => Modifiers::CONTROL,
+            "Alt" => Modifiers::ALT,
+            "Shift" => Modifiers::SHIFT,
+            "Super" => Modifiers::SUPER,
+            _ => return Err(ParseError::new("unknown modifier", token)),
+        };
+        if is_optional {
+            optional |= modifier;
+        } else {
+            mods |= modifier;
+        }
+        rest = tail;
+    }
+    let key = match key_char(rest) {
+        _ if rest.is_empty() => return Err(ParseError::new("missing key", token)),
+        Some(ch) => char_key(ch),
+        None if is_key_name(rest) => rest.to_string(),
+        None => return Err(ParseError::new("unknown key name", token)),
+    };
+    Ok(Chord::new(mods, key).with_optional(optional))
+}
+
+/// Write a chord in keyseq's notation.
+pub fn format_chord(mods: Modifiers, key: &str) -> String {
+    if mods.is_empty() {
+        key.to_string()
+    } else {
+        format!("{mods}-{key}")
+    }
+}
+
+/// Write a key sequence in keyseq's notation.
+pub fn format_keyseq<S: AsRef<str>>(seq: &[(Modifiers, S)]) -> String {
+    seq.iter()
+        .map(|(mods, key)| format_chord(*mods, key.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minus_and_modifier_names() {
+        assert_eq!(
+            parse_chord("Ctrl--"),
+            Ok((Modifiers::CONTROL, "-".to_string()))
+        );
+        assert_eq!(parse_chord("-"), Ok((Modifiers::NONE, "-".to_string())));
+        assert_eq!(
+            parse_chord("Control-Shift-F5"),
+            Ok((Modifiers::CONTROL | Modifiers::SHIFT, "F5".to_string()))
+        );
+        assert_eq!(
+            parse_chord("Ctrl-Meta-A").unwrap_err().message,
+            "unknown modifier"
+        );
+    }
+
+    #[test]
+    fn notations_convert() {
+        let seq = Notation::Emacs.parse("C-x C-s").unwrap();
+        let converted: Vec<String> = Notation::ALL.iter().map(|n| n.format(&seq)).collect();
+        assert_eq!(
+            converted,
+            [
+                "Ctrl-X Ctrl-S",
+                "C-x C-s",
+                "<C-x><C-s>",
+                "ctrl+x ctrl+s",
+                "⌃X ⌃S"
+            ]
+        );
+        assert!(Notation::MacGlyphs.parse("⌃X").is_err());
+        assert_eq!(Notation::from_name("vscode"), Some(Notation::VsCode));
+    }
+
+    #[test]
+    fn every_notation_round_trips() {
+        let mut seqs: Vec<KeySeq<String>> = (b'!'..=b'~')
+            .filter(|ch| !ch.is_ascii_lowercase())
+            .flat_map(|ch| {
+                let key = char_key(ch as char);
+                [(Modifiers::NONE, key.clone()), (Modifiers::CONTROL, key)]
+            })
+            .map(|chord| vec![chord])
+            .collect();
+        for keys in [
+            "Ctrl-X Ctrl-S",
+            "Shift-A",
+            "Ctrl-Space",
+            "Alt-Shift-Tab",
+            "Ctrl-Alt-Delete",
+            "Escape Enter Backspace",
+            "ArrowUp ArrowDown ArrowLeft ArrowRight",
+            "Home End PageUp PageDown",
+            "F1 Super-F12",
+            "Leader F",
+        ] {
+            seqs.push(parse_keyseq(keys).unwrap());
+        }
+        let readable = Notation::ALL.iter().filter(|n| **n != Notation::MacGlyphs);
+        for from in readable.clone() {
+            for to in readable.clone() {
+                for seq in &seqs {
+                    let text = from.format(seq);
+                    let read = from.parse(&text);
+                    assert_eq!(read.as_ref(), Ok(seq), "{} `{text}`", from.name());
+                    let converted = to.format(&read.unwrap());
+                    assert_eq!(
+                        to.parse(&converted).as_ref(),
+                        Ok(seq),
+                        "{} `{text}` to {} `{converted}`",
+                        from.name(),
+                        to.name()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn binding_files() {
+        let text = "# comment\nCtrl-= = zoom-in\n\n[Edit]\nCtrl-Z = undo\n";
+        let bindings = parse_bindings(text, Notation::Keyseq).unwrap();
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[0].keys, [(Modifiers::CONTROL, "=".to_string())]);
+        assert_eq!((bindings[1].line, bindings[1].group.as_str()), (5, "Edit"));
+        let errors = parse_bindings("Ctrl-Q\nHyper-A = x\nCtrl-Sapce = save\n", Notation::Keyseq)
+            .unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[2].1.message, "unknown key name");
+
+        // Optional modifiers bind each combination on the same line.
+        let bindings = parse_bindings("Ctrl-Shift?-X = cut\n", Notation::Keyseq).unwrap();
+        let keys: Vec<_> = bindings
+            .iter()
+            .map(|b| (b.line, format_keyseq(&b.keys)))
+            .collect();
+        assert_eq!(
+            keys,
+            [(1, "Ctrl-X".to_string()), (1, "Ctrl-Shift-X".to_string())]
+        );
+    }
+}
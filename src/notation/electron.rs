@@ -0,0 +1,179 @@
+//! Electron accelerators, e.g., `CmdOrCtrl+Shift+P`
+//!
+//! An accelerator is a single chord of modifiers and a key joined by `+`.
+//! `CmdOrCtrl` is Super on macOS and Control elsewhere, see [CMD_OR_CTRL].
+//! Names are case-insensitive. Electron names fewer keys than keyseq, so only
+//! those can be written.
+//!
+//! ```
+//! use keyseq::{Modifiers, notation::electron::{self, CMD_OR_CTRL}};
+//! assert_eq!(electron::parse("CmdOrCtrl+Shift+P").unwrap(),
+//!            (CMD_OR_CTRL | Modifiers::SHIFT, "P".to_string()));
+//! assert_eq!(electron::format(CMD_OR_CTRL | Modifiers::SHIFT, "P").unwrap(), "CmdOrCtrl+Shift+P");
+//! assert_eq!(electron::format(Modifiers::ALT, "+").unwrap(), "Alt+Plus");
+//! assert_eq!(electron::format(Modifiers::NONE, "F25"), None);
+//! ```
+use super::{char_key, key_char, split_plus, ParseError};
+use crate::Modifiers;
+
+/// The modifier of `CmdOrCtrl` on this platform.
+pub const CMD_OR_CTRL: Modifiers = if cfg!(target_os = "macos") {
+    Modifiers::SUPER
+} else {
+    Modifiers::CONTROL
+};
+
+/// Electron key names and their keyseq names. The first Electron name of a
+/// keyseq name is the one written.
+const NAMES: &[(&str, &str)] = &[
+    ("Plus", "+"),
+    ("Space", "Space"),
+    ("Tab", "Tab"),
+    ("Enter", "Enter"),
+    ("Return", "Enter"),
+    ("Escape", "Escape"),
+    ("Esc", "Escape"),
+    ("Backspace", "Backspace"),
+    ("Delete", "Delete"),
+    ("Insert", "Insert"),
+    ("Up", "ArrowUp"),
+    ("Down", "ArrowDown"),
+    ("Left", "ArrowLeft"),
+    ("Right", "ArrowRight"),
+    ("Home", "Home"),
+    ("End", "End"),
+    ("PageUp", "PageUp"),
+    ("PageDown", "PageDown"),
+    ("Capslock", "CapsLock"),
+    ("Numlock", "NumLock"),
+    ("Scrolllock", "ScrollLock"),
+    ("PrintScreen", "PrintScreen"),
+    ("VolumeUp", "AudioVolumeUp"),
+    ("VolumeDown", "AudioVolumeDown"),
+    ("VolumeMute", "AudioVolumeMute"),
+    ("MediaNextTrack", "MediaTrackNext"),
+    ("MediaPreviousTrack", "MediaTrackPrevious"),
+    ("MediaStop", "MediaStop"),
+    ("MediaPlayPause", "MediaPlayPause"),
+];
+
+/// Read an accelerator.
+pub fn parse(text: &str) -> Result<(Modifiers, String), ParseError> {
+    let token = text.trim();
+    if token.contains(char::is_whitespace) {
+        return Err(ParseError::new(
+            "an accelerator is a single chord, not a sequence",
+            token,
+        ));
+    }
+    let (names, key) = split_plus(token)?;
+    let mut mods = Modifiers::NONE;
+    for name in names {
+        mods |= match name.to_ascii_lowercase().as_str() {
+            "cmdorctrl" | "commandorcontrol" => CMD_OR_CTRL,
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "alt" | "option" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            "cmd" | "command" | "super" | "meta" => Modifiers::SUPER,
+            _ => return Err(ParseError::new("unknown modifier", token)),
+        };
+    }
+    if let Some(ch) = key_char(key) {
+        return Ok((mods, char_key(ch)));
+    }
+    if let Some((_, name)) = NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(key)) {
+        return Ok((mods, name.to_string()));
+    }
+    match key.strip_prefix(['f', 'F']).map(str::parse::<u8>) {
+        Some(Ok(n)) if (1..=24).contains(&n) => Ok((mods, format!("F{n}"))),
+        _ => Err(ParseError::new("unknown key name", token)),
+    }
+}
+
+/// Write an accelerator, or return `None` if Electron has no name for `key`.
+/// The platform's [CMD_OR_CTRL] is written as `CmdOrCtrl`.
+pub fn format(mods: Modifiers, key: &str) -> Option<String> {
+    let name = match NAMES.iter().find(|(_, k)| *k == key) {
+        Some((name, _)) => name,
+        None if key_char(key).is_some() => key,
+        None => match key.strip_prefix('F').map(str::parse::<u8>) {
+            Some(Ok(n)) if (1..=24).contains(&n) => key,
+            _ => return None,
+        },
+    };
+    let mut result = String::new();
+    let mut rest = mods;
+    if rest.contains(CMD_OR_CTRL) {
+        result.push_str("CmdOrCtrl+");
+        rest -= CMD_OR_CTRL;
+    }
+    for (modifier, name) in [
+        (Modifiers::CONTROL, "Ctrl"),
+        (Modifiers::ALT, "Alt"),
+        (Modifiers::SHIFT, "Shift"),
+        (Modifiers::SUPER, "Super"),
+    ] {
+        if rest.contains(modifier) {
+            result.push_str(name);
+            result.push('+');
+        }
+    }
+    result.push_str(name);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::{parse_chord, NAMED_KEYS};
+
+    #[test]
+    fn round_trips_with_keyseq_notation() {
+        for (accelerator, keyseq) in [
+            ("Alt+Shift+F4", "Alt-Shift-F4"),
+            ("Shift+Plus", "Shift-+"),
+            ("Ctrl+Alt+Delete", "Ctrl-Alt-Delete"),
+            ("Up", "ArrowUp"),
+        ] {
+            let chord = parse(accelerator).unwrap();
+            let expected = parse_chord(keyseq).unwrap();
+            assert_eq!(chord, expected, "{accelerator}");
+            // Ctrl may be written as CmdOrCtrl.
+            assert_eq!(
+                parse(&format(chord.0, &chord.1).unwrap()).unwrap(),
+                expected,
+                "{accelerator}"
+            );
+        }
+    }
+
+    #[test]
+    fn named_keys_round_trip() {
+        let mut written = 0;
+        for key in NAMED_KEYS {
+            for mods in [Modifiers::NONE, Modifiers::ALT | Modifiers::SHIFT] {
+                if let Some(text) = format(mods, key) {
+                    assert_eq!(parse(&text), Ok((mods, key.to_string())), "{text}");
+                    written += 1;
+                }
+            }
+        }
+        // Every name but `Plus`, `Return`, and `Esc`, and F1 to F24.
+        assert_eq!(written, 2 * (NAMES.len() - 3 + 24));
+        assert_eq!(format(Modifiers::NONE, "Leader"), None);
+    }
+
+    #[test]
+    fn aliases_and_errors() {
+        assert_eq!(
+            parse("commandorcontrol+q").unwrap(),
+            parse("CmdOrCtrl+Q").unwrap()
+        );
+        assert_eq!(parse("Option+Return").unwrap(), parse("Alt+Enter").unwrap());
+        assert_eq!(
+            parse("Ctrl+K Ctrl+S").unwrap_err().message,
+            "an accelerator is a single chord, not a sequence"
+        );
+        assert!(parse("AltGr+E").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Emacs key notation, e.g., `C-x C-s`, `M-<f5>`, or `C-M-%`
+//!
+//! The prefixes `C-`, `M-`, `S-`, and `s-` are Control, Meta (Alt), Shift, and
+//! Super. Emacs's `A-` reads as Alt, and `H-` reads as Super since
+//! [Modifiers] has no Hyper. An uppercase letter is a shifted letter. Named
+//! keys are written in angle brackets, e.g., `<f5>` or `<prior>`, and a few
+//! have short names like `RET` and `SPC`. The [Leader](crate::keymap::Leader)
+//! placeholder is `<leader>`.
+//!
+//! ```
+//! use keyseq::{Modifiers, notation::{emacs, parse_keyseq}};
+//! assert_eq!(emacs::parse("C-x C-s").unwrap(), parse_keyseq("Ctrl-X Ctrl-S").unwrap());
+//! assert_eq!(emacs::parse("M-<f5>").unwrap(), [(Modifiers::ALT, "F5".to_string())]);
+//! assert_eq!(emacs::format(&parse_keyseq("Ctrl-Alt-% Shift-A Enter").unwrap()), "C-M-% A RET");
+//! ```
+use super::{char_key, key_char, ParseError, NAMED_KEYS};
+use crate::{keymap::KeySeq, Modifiers};
+
+/// Emacs key names and their keyseq names. The first Emacs name of a keyseq
+/// name is the one written.
+const NAMES: &[(&str, &str)] = &[
+    ("RET", "Enter"),
+    ("return", "Enter"),
+    ("SPC", "Space"),
+    ("TAB", "Tab"),
+    ("tab", "Tab"),
+    ("ESC", "Escape"),
+    ("escape", "Escape"),
+    ("DEL", "Backspace"),
+    ("backspace", "Backspace"),
+    ("delete", "Delete"),
+    ("deletechar", "Delete"),
+    ("insert", "Insert"),
+    ("up", "ArrowUp"),
+    ("down", "ArrowDown"),
+    ("left", "ArrowLeft"),
+    ("right", "ArrowRight"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("prior", "PageUp"),
+    ("next", "PageDown"),
+    ("menu", "ContextMenu"),
+    ("print", "PrintScreen"),
+    ("pause", "Pause"),
+    ("leader", "Leader"),
+];
+
+/// Read a key sequence in Emacs notation.
+pub fn parse(text: &str) -> Result<KeySeq<String>, ParseError> {
+    text.split_whitespace().map(parse_chord).collect()
+}
+
+/// Read a chord in Emacs notation, e.g., `C-M-<return>`.
+pub fn parse_chord(token: &str) -> Result<(Modifiers, String), ParseError> {
+    let (mods, rest) = parse_modifiers(token, token)?;
+    if let Some(inner) = rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')) {
+        // Modifiers may also appear inside the brackets, e.g., `<C-f5>`.
+        let (inner_mods, name) = parse_modifiers(inner, token)?;
+        return Ok((mods | inner_mods, named_key(name, token)?));
+    }
+    match key_char(rest) {
+        _ if rest.is_empty() => Err(ParseError::new("missing key", token)),
+        Some(ch) if ch.is_uppercase() => Ok((mods | Modifiers::SHIFT, char_key(ch))),
+        Some(ch) => Ok((mods, char_key(ch))),
+        None => match NAMES.iter().find(|(name, _)| *name == rest) {
+            Some((name, _)) if name.chars().all(|c| c.is_ascii_uppercase()) => {
+                Ok((mods, named_key(name, token)?))
+            }
+            _ => Err(ParseError::new(
+                "named keys need angle brackets, e.g., `<f5>`",
+                token,
+            )),
+        },
+    }
+}
+
+fn parse_modifiers<'a>(text: &'a str, token: &str) -> Result<(Modifiers, &'a str), ParseError> {
+    let mut mods = Modifiers::NONE;
+    let mut rest = text;
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        mods |= match rest.as_bytes()[0] {
+            b'C' => Modifiers::CONTROL,
+            b'M' | b'A' => Modifiers::ALT,
+            b'S' => Modifiers::SHIFT,
+            b's' | b'H' => Modifiers::SUPER,
+            _ => return Err(ParseError::new("unknown modifier", token)),
+        };
+        rest = &rest[2..];
+    }
+    Ok((mods, rest))
+}
+
+fn named_key(name: &str, token: &str) -> Result<String, ParseError> {
+    if let Some((_, key)) = NAMES.iter().find(|(n, _)| *n == name) {
+        return Ok(key.to_string());
+    }
+    match name.strip_prefix('f').map(str::parse::<u8>) {
+        Some(Ok(n)) if (1..=35).contains(&n) => Ok(format!("F{n}")),
+        // Other named keys are written in lowercase, e.g., `<numlock>`.
+        _ => NAMED_KEYS
+            .iter()
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .map(|key| key.to_string())
+            .ok_or_else(|| ParseError::new("unknown key name", token)),
+    }
+}
+
+/// Write a chord in Emacs notation.
+pub fn format_chord(mods: Modifiers, key: &str) -> String {
+    let mut result = String::new();
+    for (modifier, prefix) in [
+        (Modifiers::CONTROL, "C-"),
+        (Modifiers::ALT, "M-"),
+        (Modifiers::SUPER, "s-"),
+    ] {
+        if mods.contains(modifier) {
+            result.push_str(prefix);
+        }
+    }
+    let shift = mods.contains(Modifiers::SHIFT);
+    match key_char(key) {
+        Some(ch) if ch.is_alphabetic() => {
+            if shift {
+                result.extend(ch.to_uppercase());
+            } else {
+                result.extend(ch.to_lowercase());
+            }
+        }
+        _ => {
+            if shift {
+                result.push_str("S-");
+            }
+            match NAMES.iter().find(|(_, k)| *k == key) {
+                Some((name, _)) if name.chars().all(|c| c.is_ascii_uppercase()) => {
+                    result.push_str(name)
+                }
+                Some((name, _)) => {
+                    result.push('<');
+                    result.push_str(name);
+                    result.push('>');
+                }
+                None if key_char(key).is_some() => result.push_str(key),
+                None => {
+                    // Keep the case of a name Emacs gives to another key,
+                    // e.g., `<Print>` since `<print>` is PrintScreen.
+                    let name = key.to_lowercase();
+                    let taken = NAMES.iter().any(|(n, _)| *n == name);
+                    result.push('<');
+                    result.push_str(if taken { key } else { &name });
+                    result.push('>');
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Write a key sequence in Emacs notation.
+pub fn format<S: AsRef<str>>(seq: &[(Modifiers, S)]) -> String {
+    seq.iter()
+        .map(|(mods, key)| format_chord(*mods, key.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::{format_keyseq, parse_keyseq};
+
+    #[test]
+    fn round_trips_with_keyseq_notation() {
+        for (emacs, keyseq) in [
+            ("C-x C-s", "Ctrl-X Ctrl-S"),
+            ("M-x", "Alt-X"),
+            ("M-<f5>", "Alt-F5"),
+            ("C-M-%", "Ctrl-Alt-%"),
+            ("C-S-<up>", "Ctrl-Shift-ArrowUp"),
+            ("s-X", "Shift-Super-X"),
+            ("C-c RET", "Ctrl-C Enter"),
+            ("C--", "Ctrl--"),
+            ("<leader> f", "Leader F"),
+        ] {
+            let seq = parse(emacs).unwrap();
+            assert_eq!(seq, parse_keyseq(keyseq).unwrap(), "{emacs}");
+            assert_eq!(format_keyseq(&seq), keyseq);
+            assert_eq!(format(&seq), emacs);
+        }
+    }
+
+    #[test]
+    fn named_keys_round_trip() {
+        for key in NAMED_KEYS {
+            for mods in [Modifiers::NONE, Modifiers::CONTROL | Modifiers::SHIFT] {
+                let text = format_chord(mods, key);
+                assert_eq!(parse_chord(&text), Ok((mods, key.to_string())), "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn aliases_and_errors() {
+        assert_eq!(parse("<C-return>").unwrap(), parse("C-RET").unwrap());
+        assert_eq!(parse("H-a").unwrap(), parse("s-a").unwrap());
+        assert!(parse("C-<nope>").is_err());
+        assert_eq!(parse("<NumLock>").unwrap(), parse("<numlock>").unwrap());
+        assert_eq!(
+            parse("C-return").unwrap_err().message,
+            "named keys need angle brackets, e.g., `<f5>`"
+        );
+        assert_eq!(parse("X-a").unwrap_err().message, "unknown modifier");
+    }
+}
@@ -0,0 +1,269 @@
+//! Vim key notation, e.g., `<C-w>h`, `<M-j>`, `<CR>`, or `<leader>f`
+//!
+//! Plain characters are keys, and an uppercase letter is a shifted letter.
+//! Other keys and chords with modifiers are written in angle brackets. The
+//! prefixes `C-`, `M-` or `A-`, `S-`, and `D-` are Control, Alt, Shift, and
+//! Super. A literal `<` is written `<lt>`, and `>` with modifiers is, e.g.,
+//! `<C->>`. The `<leader>` is keyseq's [Leader](crate::keymap::Leader)
+//! placeholder, or expands to the leader given to [parse_with_leader()], e.g.,
+//! [DEFAULT_LEADER].
+//!
+//! ```
+//! use keyseq::{Modifiers, notation::{vim, parse_keyseq}};
+//! assert_eq!(vim::parse("<C-w>h").unwrap(), parse_keyseq("Ctrl-W H").unwrap());
+//! assert_eq!(vim::parse("<leader>ff").unwrap(), parse_keyseq("Leader F F").unwrap());
+//! assert_eq!(vim::parse_with_leader("<leader>ff", "<Space>").unwrap(),
+//!            parse_keyseq("Space F F").unwrap());
+//! assert_eq!(vim::format(&parse_keyseq("Alt-J Shift-G Escape").unwrap()), "<M-j>G<Esc>");
+//!
+//! let error = vim::parse("<C-w").unwrap_err();
+//! assert_eq!(error.to_string(), "unclosed `<`, write `<lt>` for a literal `<` in `<C-w`");
+//! ```
+use super::{char_key, key_char, ParseError, NAMED_KEYS};
+use crate::{keymap::KeySeq, Modifiers};
+
+/// Vim's leader when none is set.
+pub const DEFAULT_LEADER: &str = "\\";
+
+/// Vim key names and their keyseq names. The first Vim name of a keyseq name
+/// is the one written.
+const NAMES: &[(&str, &str)] = &[
+    ("CR", "Enter"),
+    ("Enter", "Enter"),
+    ("Return", "Enter"),
+    ("Esc", "Escape"),
+    ("Tab", "Tab"),
+    ("BS", "Backspace"),
+    ("Del", "Delete"),
+    ("Space", "Space"),
+    ("Insert", "Insert"),
+    ("Up", "ArrowUp"),
+    ("Down", "ArrowDown"),
+    ("Left", "ArrowLeft"),
+    ("Right", "ArrowRight"),
+    ("Home", "Home"),
+    ("End", "End"),
+    ("PageUp", "PageUp"),
+    ("PageDown", "PageDown"),
+    ("lt", "<"),
+    ("Bar", "|"),
+    ("Bslash", "\\"),
+    ("Leader", "Leader"),
+];
+
+/// Read a key sequence in Vim notation, keeping `<leader>` as the
+/// placeholder.
+pub fn parse(text: &str) -> Result<KeySeq<String>, ParseError> {
+    parse_keys(text, None)
+}
+
+/// Read a key sequence in Vim notation where `<leader>` expands to `leader`,
+/// which is itself in Vim notation, e.g., `<Space>` or `,`.
+pub fn parse_with_leader(text: &str, leader: &str) -> Result<KeySeq<String>, ParseError> {
+    parse_keys(text, Some(leader))
+}
+
+fn parse_keys(text: &str, leader: Option<&str>) -> Result<KeySeq<String>, ParseError> {
+    let mut seq = vec![];
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        if ch != '<' {
+            seq.push(match ch {
+                ' ' => (Modifiers::NONE, "Space".to_string()),
+                _ if ch.is_uppercase() => (Modifiers::SHIFT, char_key(ch)),
+                _ => (Modifiers::NONE, char_key(ch)),
+            });
+            rest = &rest[ch.len_utf8()..];
+            continue;
+        }
+        let Some(mut end) = rest.find('>') else {
+            return Err(ParseError::new(
+                "unclosed `<`, write `<lt>` for a literal `<`",
+                rest,
+            ));
+        };
+        if only_modifiers(&rest[1..end]) && rest[end + 1..].starts_with('>') {
+            // The key is `>` itself, e.g., `<C->>`.
+            end += 1;
+        }
+        let token = &rest[..=end];
+        let inner = &rest[1..end];
+        if inner.eq_ignore_ascii_case("leader") {
+            match leader {
+                Some(leader) => {
+                    let keys = parse_keys(leader, None)?;
+                    if keys.iter().any(|(_, key)| key == "Leader") {
+                        return Err(ParseError::new(
+                            "the leader cannot contain `<leader>`",
+                            token,
+                        ));
+                    }
+                    seq.extend(keys);
+                }
+                None => seq.push((Modifiers::NONE, "Leader".to_string())),
+            }
+        } else {
+            seq.push(parse_bracketed(inner, token)?);
+        }
+        rest = &rest[end + 1..];
+    }
+    Ok(seq)
+}
+
+/// Return true if `text` is only modifier prefixes, e.g., `C-S-`.
+fn only_modifiers(text: &str) -> bool {
+    !text.is_empty()
+        && text.len().is_multiple_of(2)
+        && text
+            .as_bytes()
+            .chunks(2)
+            .all(|prefix| prefix[1] == b'-' && b"CMASDcmasd".contains(&prefix[0]))
+}
+
+fn parse_bracketed(inner: &str, token: &str) -> Result<(Modifiers, String), ParseError> {
+    let mut mods = Modifiers::NONE;
+    let mut name = inner;
+    while name.len() > 2 && name.as_bytes()[1] == b'-' {
+        mods |= match name.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => Modifiers::CONTROL,
+            b'M' | b'A' => Modifiers::ALT,
+            b'S' => Modifiers::SHIFT,
+            b'D' => Modifiers::SUPER,
+            _ => return Err(ParseError::new("unknown modifier", token)),
+        };
+        name = &name[2..];
+    }
+    if let Some(ch) = key_char(name) {
+        if ch.is_uppercase()
+            && mods.contains(Modifiers::CONTROL)
+            && !mods.contains(Modifiers::SHIFT)
+        {
+            // Vim ignores the case of a letter with Control.
+            return Err(ParseError::new(
+                "ambiguous case, write `C-` with a lowercase letter and add `S-` for Shift",
+                token,
+            ));
+        }
+        let shift = if ch.is_uppercase() {
+            Modifiers::SHIFT
+        } else {
+            Modifiers::NONE
+        };
+        return Ok((mods | shift, char_key(ch)));
+    }
+    if let Some((_, key)) = NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+        return Ok((mods, key.to_string()));
+    }
+    if let Some(key) = NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(name)) {
+        // Other named keys keep keyseq's name, e.g., `<NumLock>`.
+        return Ok((mods, key.to_string()));
+    }
+    match name.strip_prefix(['F', 'f']).map(str::parse::<u8>) {
+        Some(Ok(n)) if (1..=35).contains(&n) => Ok((mods, format!("F{n}"))),
+        _ if name.is_empty() => Err(ParseError::new("missing key", token)),
+        _ => Err(ParseError::new(
+            "unknown key name, write `<lt>` for a literal `<`",
+            token,
+        )),
+    }
+}
+
+/// Write a chord in Vim notation.
+pub fn format_chord(mods: Modifiers, key: &str) -> String {
+    let ch = key_char(key);
+    let name = NAMES.iter().find(|(_, k)| *k == key).map(|(n, _)| *n);
+    match (ch, name) {
+        // Plain characters are written as is, except those with a name.
+        (Some(ch), None) if mods.is_empty() => ch.to_lowercase().collect(),
+        (Some(ch), None) if mods == Modifiers::SHIFT && ch.is_alphabetic() => key.to_string(),
+        _ => {
+            let mut result = String::from("<");
+            for (modifier, prefix) in [
+                (Modifiers::CONTROL, "C-"),
+                (Modifiers::ALT, "M-"),
+                (Modifiers::SHIFT, "S-"),
+                (Modifiers::SUPER, "D-"),
+            ] {
+                if mods.contains(modifier) {
+                    result.push_str(prefix);
+                }
+            }
+            match (ch, name) {
+                (_, Some(name)) => result.push_str(name),
+                (Some(ch), None) => result.extend(ch.to_lowercase()),
+                (None, None) => result.push_str(key),
+            }
+            result.push('>');
+            result
+        }
+    }
+}
+
+/// Write a key sequence in Vim notation.
+pub fn format<S: AsRef<str>>(seq: &[(Modifiers, S)]) -> String {
+    seq.iter()
+        .map(|(mods, key)| format_chord(*mods, key.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::parse_keyseq;
+
+    #[test]
+    fn round_trips_with_keyseq_notation() {
+        for (vim, keyseq) in [
+            ("<C-w>h", "Ctrl-W H"),
+            ("<M-j>", "Alt-J"),
+            ("<CR><Esc>", "Enter Escape"),
+            ("<lt>", "<"),
+            ("<S-Tab>", "Shift-Tab"),
+            ("<C-S-a>", "Ctrl-Shift-A"),
+            ("gg", "G G"),
+            ("<F5>", "F5"),
+            ("<C->>", "Ctrl->"),
+            ("<M-->>", "Alt-- >"),
+            ("<Leader>f", "Leader F"),
+        ] {
+            let seq = parse(vim).unwrap();
+            assert_eq!(seq, parse_keyseq(keyseq).unwrap(), "{vim}");
+            assert_eq!(format(&seq), vim);
+        }
+    }
+
+    #[test]
+    fn named_keys_round_trip() {
+        for key in NAMED_KEYS {
+            for mods in [Modifiers::NONE, Modifiers::CONTROL | Modifiers::SHIFT] {
+                let text = format_chord(mods, key);
+                assert_eq!(parse(&text), Ok(vec![(mods, key.to_string())]), "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn leader_and_errors() {
+        assert_eq!(
+            parse_with_leader("<leader>w", DEFAULT_LEADER).unwrap(),
+            parse("\\w").unwrap()
+        );
+        assert_eq!(
+            parse_with_leader("<Leader>w", ",").unwrap(),
+            parse(",w").unwrap()
+        );
+        assert_eq!(
+            parse_with_leader("<leader>", "<leader>")
+                .unwrap_err()
+                .message,
+            "the leader cannot contain `<leader>`"
+        );
+        assert!(parse("<C-A>").is_err());
+        assert!(parse("<X-a>").is_err());
+        assert_eq!(
+            parse("<<C-a>").unwrap_err().message,
+            "unknown key name, write `<lt>` for a literal `<`"
+        );
+        assert_eq!(parse("<>").unwrap_err().message, "missing key");
+    }
+}
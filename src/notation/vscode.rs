@@ -0,0 +1,161 @@
+//! VS Code key binding notation, e.g., `ctrl+shift+p` or `cmd+k cmd+s`
+//!
+//! Modifiers and keys are joined by `+` and chords are separated by spaces.
+//! Names are case-insensitive. The Super modifier goes by `cmd`, `meta`,
+//! `win`, or `super`. The [Leader](crate::keymap::Leader) placeholder is
+//! `leader`.
+//!
+//! ```
+//! use keyseq::{Modifiers, notation::{vscode, parse_keyseq}};
+//! assert_eq!(vscode::parse("ctrl+shift+p").unwrap(), parse_keyseq("Ctrl-Shift-P").unwrap());
+//! assert_eq!(vscode::parse("cmd+k cmd+s").unwrap(), parse_keyseq("Super-K Super-S").unwrap());
+//! assert_eq!(vscode::format(&parse_keyseq("Ctrl-K Ctrl-ArrowUp").unwrap()), "ctrl+k ctrl+up");
+//! ```
+use super::{char_key, key_char, split_plus, ParseError, NAMED_KEYS};
+use crate::{keymap::KeySeq, Modifiers};
+
+/// VS Code key names and their keyseq names.
+const NAMES: &[(&str, &str)] = &[
+    ("enter", "Enter"),
+    ("escape", "Escape"),
+    ("tab", "Tab"),
+    ("space", "Space"),
+    ("backspace", "Backspace"),
+    ("delete", "Delete"),
+    ("insert", "Insert"),
+    ("up", "ArrowUp"),
+    ("down", "ArrowDown"),
+    ("left", "ArrowLeft"),
+    ("right", "ArrowRight"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("pageup", "PageUp"),
+    ("pagedown", "PageDown"),
+    ("capslock", "CapsLock"),
+    ("pausebreak", "Pause"),
+    ("contextmenu", "ContextMenu"),
+    ("leader", "Leader"),
+];
+
+/// The name written for the Super modifier on this platform.
+const SUPER: &str = if cfg!(target_os = "macos") {
+    "cmd"
+} else if cfg!(target_os = "windows") {
+    "win"
+} else {
+    "meta"
+};
+
+/// Read a key sequence in VS Code notation.
+pub fn parse(text: &str) -> Result<KeySeq<String>, ParseError> {
+    text.split_whitespace().map(parse_chord).collect()
+}
+
+/// Read a chord in VS Code notation, e.g., `ctrl+shift+p`.
+pub fn parse_chord(token: &str) -> Result<(Modifiers, String), ParseError> {
+    let (names, key) = split_plus(token)?;
+    let mut mods = Modifiers::NONE;
+    for name in names {
+        mods |= match name.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "alt" | "option" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            "cmd" | "meta" | "win" | "super" => Modifiers::SUPER,
+            _ => return Err(ParseError::new("unknown modifier", token)),
+        };
+    }
+    Ok((mods, parse_key(key, token)?))
+}
+
+fn parse_key(key: &str, token: &str) -> Result<String, ParseError> {
+    if let Some(ch) = key_char(key) {
+        return Ok(char_key(ch));
+    }
+    if let Some((_, name)) = NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(key)) {
+        return Ok(name.to_string());
+    }
+    match key.strip_prefix(['f', 'F']).map(str::parse::<u8>) {
+        Some(Ok(n)) if (1..=35).contains(&n) => Ok(format!("F{n}")),
+        // Other named keys are written in lowercase, e.g., `numlock`.
+        _ => NAMED_KEYS
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(key))
+            .map(|name| name.to_string())
+            .ok_or_else(|| ParseError::new("unknown key name", token)),
+    }
+}
+
+/// Write a chord in VS Code notation.
+pub fn format_chord(mods: Modifiers, key: &str) -> String {
+    let mut result = String::new();
+    for (modifier, name) in [
+        (Modifiers::CONTROL, "ctrl"),
+        (Modifiers::SHIFT, "shift"),
+        (Modifiers::ALT, "alt"),
+        (Modifiers::SUPER, SUPER),
+    ] {
+        if mods.contains(modifier) {
+            result.push_str(name);
+            result.push('+');
+        }
+    }
+    match NAMES.iter().find(|(_, k)| *k == key) {
+        Some((name, _)) => result.push_str(name),
+        None => result.push_str(&key.to_lowercase()),
+    }
+    result
+}
+
+/// Write a key sequence in VS Code notation.
+pub fn format<S: AsRef<str>>(seq: &[(Modifiers, S)]) -> String {
+    seq.iter()
+        .map(|(mods, key)| format_chord(*mods, key.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::parse_keyseq;
+
+    #[test]
+    fn round_trips_with_keyseq_notation() {
+        for (vscode, keyseq) in [
+            ("ctrl+shift+p", "Ctrl-Shift-P"),
+            ("ctrl+k ctrl+s", "Ctrl-K Ctrl-S"),
+            ("alt+f4", "Alt-F4"),
+            ("shift+enter", "Shift-Enter"),
+            ("ctrl+=", "Ctrl-="),
+            ("ctrl++", "Ctrl-+"),
+            ("pagedown", "PageDown"),
+            ("leader f", "Leader F"),
+        ] {
+            let seq = parse(vscode).unwrap();
+            assert_eq!(seq, parse_keyseq(keyseq).unwrap(), "{vscode}");
+            assert_eq!(format(&seq), vscode);
+        }
+    }
+
+    #[test]
+    fn named_keys_round_trip() {
+        for key in NAMED_KEYS {
+            for mods in [Modifiers::NONE, Modifiers::CONTROL | Modifiers::SHIFT] {
+                let text = format_chord(mods, key);
+                assert_eq!(parse_chord(&text), Ok((mods, key.to_string())), "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn aliases_and_errors() {
+        let cmd = parse("Cmd+K").unwrap();
+        assert_eq!(parse("meta+k").unwrap(), cmd);
+        assert_eq!(parse("win+k").unwrap(), cmd);
+        assert_eq!(parse("option+x").unwrap(), parse("alt+x").unwrap());
+        assert_eq!(parse("hyper+x").unwrap_err().message, "unknown modifier");
+        assert_eq!(parse("ctrl+").unwrap_err().message, "missing key");
+        assert_eq!(parse("ctrl++x").unwrap_err().message, "missing modifier");
+        assert!(parse("ctrl+nope").is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Recognize taps, double taps, holds, and releases
+//!
+//! A [PressRecognizer] matches chords by how they are pressed, see [Press]. It
+//! needs to know the time of each key event, which may come from bevy's
+//! `Time::elapsed()` or any other clock.
+//!
+//! ```
+//! use std::time::Duration;
+//! use keyseq::{Chord, Modifiers, Press, press::PressRecognizer};
+//!
+//! let mut recognizer = PressRecognizer::default();
+//! let search = recognizer.add(Chord::from_modifiers(Modifiers::SHIFT).with_press(Press::DoubleTap));
+//! let hold = recognizer.add(Chord::new(Modifiers::NONE, "Space").with_press(Press::Hold(Duration::from_millis(500))));
+//!
+//! let ms = Duration::from_millis;
+//! recognizer.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(0));
+//! recognizer.release_modifier(Modifiers::SHIFT, ms(50));
+//! recognizer.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(150));
+//! recognizer.release_modifier(Modifiers::SHIFT, ms(200));
+//! assert_eq!(recognizer.drain().collect::<Vec<_>>(), [search]);
+//!
+//! recognizer.press(Modifiers::NONE, "Space", ms(1000));
+//! recognizer.update(ms(1499));
+//! assert_eq!(recognizer.drain().count(), 0);
+//! recognizer.update(ms(1500));
+//! assert_eq!(recognizer.drain().collect::<Vec<_>>(), [hold]);
+//! ```
+use super::{modifier_chord::ModifierChordRecognizer, Chord, Modifiers, Press};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Target<K> {
+    Key(K),
+    Modifier(Modifiers),
+}
+
+#[derive(Clone, Debug)]
+struct Held<K> {
+    target: Target<K>,
+    /// The modifiers of the chord, which include the target if it is a modifier.
+    mods: Modifiers,
+    start: Duration,
+    /// Another key was pressed while this one was held.
+    interrupted: bool,
+    /// The hold bindings that have fired.
+    fired: Vec<usize>,
+}
+
+impl<K> Held<K> {
+    fn key(&self) -> Option<&K> {
+        match self.target {
+            Target::Key(ref key) => Some(key),
+            Target::Modifier(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct LastTap<K> {
+    mods: Modifiers,
+    key: Option<K>,
+    time: Duration,
+    /// Tap bindings waiting to see whether a double tap follows.
+    deferred: Vec<usize>,
+}
+
+/// Recognizes chords that are tapped, double tapped, held, or released as well
+/// as pressed.
+///
+/// Add chords with a [Press] qualifier, feed it key events with their time,
+/// call [update](PressRecognizer::update) each frame, and
+/// [drain](PressRecognizer::drain) the indices of the chords that fired.
+#[derive(Clone, Debug)]
+pub struct PressRecognizer<K> {
+    bindings: Vec<Chord<K>>,
+    /// The longest a key may be held and still count as a tap.
+    pub tap_timeout: Duration,
+    /// The longest time between two taps to count as a double tap.
+    pub double_tap_timeout: Duration,
+    held: Vec<Held<K>>,
+    /// Decides which chords of only modifiers were tapped.
+    modifiers: ModifierChordRecognizer,
+    /// When the first of the modifiers held was pressed.
+    modifier_start: Duration,
+    last_tap: Option<LastTap<K>>,
+    events: VecDeque<usize>,
+}
+
+impl<K> Default for PressRecognizer<K> {
+    fn default() -> Self {
+        PressRecognizer {
+            bindings: vec![],
+            tap_timeout: Duration::from_millis(250),
+            double_tap_timeout: Duration::from_millis(300),
+            held: vec![],
+            modifiers: ModifierChordRecognizer::default(),
+            modifier_start: Duration::ZERO,
+            last_tap: None,
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> PressRecognizer<K> {
+    /// Add a chord and return its index.
+    pub fn add(&mut self, chord: Chord<K>) -> usize {
+        self.bindings.push(chord);
+        self.bindings.len() - 1
+    }
+
+    /// Add a chord and return self.
+    pub fn with(mut self, chord: Chord<K>) -> Self {
+        self.add(chord);
+        self
+    }
+
+    /// Return the chords that have been added.
+    pub fn bindings(&self) -> &[Chord<K>] {
+        &self.bindings
+    }
+
+    /// Record a non-modifier key press with the modifiers held at time `now`.
+    pub fn press(&mut self, mods: Modifiers, key: K, now: Duration) {
+        self.press_target(mods, Target::Key(key), now);
+    }
+
+    /// Record a modifier key press at time `now`. The `mods` are the other
+    /// modifiers already held.
+    pub fn press_modifier(&mut self, mods: Modifiers, modifier: Modifiers, now: Duration) {
+        self.press_target(mods | modifier, Target::Modifier(modifier), now);
+    }
+
+    /// Record a non-modifier key release at time `now`.
+    pub fn release(&mut self, key: &K, now: Duration) {
+        self.release_target(&Target::Key(key.clone()), now);
+    }
+
+    /// Record a modifier key release at time `now`.
+    pub fn release_modifier(&mut self, modifier: Modifiers, now: Duration) {
+        self.release_target(&Target::Modifier(modifier), now);
+    }
+
+    /// Fire holds and deferred taps that are due by time `now`.
+    pub fn update(&mut self, now: Duration) {
+        for i in 0..self.held.len() {
+            let held = &self.held[i];
+            if held.interrupted {
+                continue;
+            }
+            let elapsed = now.saturating_sub(held.start);
+            let due: Vec<usize> = self
+                .matching(
+                    held.mods,
+                    held.key(),
+                    |press| matches!(press, Press::Hold(duration) if elapsed >= duration),
+                )
+                .filter(|index| !held.fired.contains(index))
+                .collect();
+            self.events.extend(due.iter().copied());
+            self.held[i].fired.extend(due);
+        }
+        if let Some(ref last_tap) = self.last_tap {
+            if now.saturating_sub(last_tap.time) > self.double_tap_timeout {
+                self.flush_last_tap();
+            }
+        }
+    }
+
+    /// Return true if a key is held or a tap may become a double tap.
+    pub fn is_pending(&self) -> bool {
+        !self.held.is_empty() || self.last_tap.is_some()
+    }
+
+    /// Drain the indices of the chords that fired.
+    pub fn drain(&mut self) -> impl Iterator<Item = usize> + '_ {
+        self.events.drain(..)
+    }
+
+    fn press_target(&mut self, mods: Modifiers, target: Target<K>, now: Duration) {
+        self.update(now);
+        let key = match target {
+            Target::Key(ref key) => {
+                self.modifiers.press_key();
+                Some(key.clone())
+            }
+            Target::Modifier(modifier) => {
+                if self.modifiers.held().is_empty() {
+                    self.modifier_start = now;
+                }
+                self.modifiers.press_modifier(modifier);
+                None
+            }
+        };
+        if self
+            .last_tap
+            .as_ref()
+            .is_some_and(|tap| tap.mods != mods || tap.key != key)
+        {
+            // Something else was pressed, so it was only a single tap.
+            self.flush_last_tap();
+        }
+        for held in &mut self.held {
+            held.interrupted = true;
+        }
+        let fired: Vec<usize> = self
+            .matching(mods, key.as_ref(), |press| press == Press::Press)
+            .collect();
+        self.events.extend(fired);
+        self.held.push(Held {
+            target,
+            mods,
+            start: now,
+            interrupted: false,
+            fired: vec![],
+        });
+    }
+
+    fn release_target(&mut self, target: &Target<K>, now: Duration) {
+        self.update(now);
+        let Some(index) = self.held.iter().position(|held| held.target == *target) else {
+            return;
+        };
+        let held = self.held.remove(index);
+        let key = held.key().cloned();
+        let fired: Vec<usize> = self
+            .matching(held.mods, key.as_ref(), |press| press == Press::Release)
+            .collect();
+        self.events.extend(fired);
+        let mods = match *target {
+            Target::Key(_) => {
+                if held.interrupted || now.saturating_sub(held.start) > self.tap_timeout {
+                    return;
+                }
+                held.mods
+            }
+            Target::Modifier(modifier) => {
+                self.modifiers.release_modifier(modifier);
+                let chord = self.modifiers.drain().next();
+                match chord {
+                    Some(mods) if now.saturating_sub(self.modifier_start) <= self.tap_timeout => {
+                        mods
+                    }
+                    _ => return,
+                }
+            }
+        };
+        // It was a tap.
+        if self.last_tap.take().is_some() {
+            // The previous tap is known to match since `press_target()` flushes
+            // any other tap.
+            let fired: Vec<usize> = self
+                .matching(mods, key.as_ref(), |press| press == Press::DoubleTap)
+                .collect();
+            self.events.extend(fired);
+            return;
+        }
+        let taps: Vec<usize> = self
+            .matching(mods, key.as_ref(), |press| press == Press::Tap)
+            .collect();
+        let double_tap_possible = self
+            .matching(mods, key.as_ref(), |press| press == Press::DoubleTap)
+            .next()
+            .is_some();
+        if double_tap_possible {
+            self.last_tap = Some(LastTap {
+                mods,
+                key,
+                time: now,
+                deferred: taps,
+            });
+        } else {
+            self.events.extend(taps);
+        }
+    }
+
+    fn flush_last_tap(&mut self) {
+        if let Some(last_tap) = self.last_tap.take() {
+            self.events.extend(last_tap.deferred);
+        }
+    }
+
+    fn matching<'a>(
+        &'a self,
+        mods: Modifiers,
+        key: Option<&'a K>,
+        press: impl Fn(Press) -> bool + 'a,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.bindings
+            .iter()
+            .enumerate()
+            .filter(move |(_, chord)| {
+                press(chord.press) && chord.key.as_ref() == key && chord.matches_modifiers(mods)
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn tap_modifier_alone() {
+        let mut r = PressRecognizer::<&str>::default();
+        let tap = r.add(Chord::from_modifiers(Modifiers::CONTROL));
+        r.press_modifier(Modifiers::NONE, Modifiers::CONTROL, ms(0));
+        r.release_modifier(Modifiers::CONTROL, ms(100));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [tap]);
+
+        // Not a tap if another key was pressed.
+        r.press_modifier(Modifiers::NONE, Modifiers::CONTROL, ms(1000));
+        r.press(Modifiers::CONTROL, "C", ms(1010));
+        r.release(&"C", ms(1020));
+        r.release_modifier(Modifiers::CONTROL, ms(1030));
+        assert_eq!(r.drain().count(), 0);
+
+        // Not a tap if held too long.
+        r.press_modifier(Modifiers::NONE, Modifiers::CONTROL, ms(2000));
+        r.release_modifier(Modifiers::CONTROL, ms(2500));
+        assert_eq!(r.drain().count(), 0);
+
+        // Releasing either modifier first taps Ctrl-Shift.
+        let both = r.add(Chord::from_modifiers(Modifiers::CONTROL | Modifiers::SHIFT));
+        r.press_modifier(Modifiers::NONE, Modifiers::CONTROL, ms(3000));
+        r.press_modifier(Modifiers::CONTROL, Modifiers::SHIFT, ms(3010));
+        r.release_modifier(Modifiers::CONTROL, ms(3020));
+        r.release_modifier(Modifiers::SHIFT, ms(3030));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [both]);
+    }
+
+    #[test]
+    fn tap_defers_for_double_tap() {
+        let mut r = PressRecognizer::<&str>::default();
+        let tap = r.add(Chord::from_modifiers(Modifiers::SHIFT));
+        let double = r.add(Chord::from_modifiers(Modifiers::SHIFT).with_press(Press::DoubleTap));
+        r.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(0));
+        r.release_modifier(Modifiers::SHIFT, ms(50));
+        assert_eq!(r.drain().count(), 0);
+        r.update(ms(400));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [tap]);
+
+        r.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(1000));
+        r.release_modifier(Modifiers::SHIFT, ms(1050));
+        r.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(1100));
+        r.release_modifier(Modifiers::SHIFT, ms(1150));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [double]);
+
+        // Pressing another key ends the wait.
+        r.press_modifier(Modifiers::NONE, Modifiers::SHIFT, ms(2000));
+        r.release_modifier(Modifiers::SHIFT, ms(2050));
+        r.press(Modifiers::NONE, "A", ms(2100));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [tap]);
+    }
+
+    #[test]
+    fn hold_fires_once() {
+        let mut r = PressRecognizer::default();
+        let hold = r.add(Chord::new(Modifiers::NONE, "Space").with_press(Press::Hold(ms(500))));
+        r.press(Modifiers::NONE, "Space", ms(0));
+        r.update(ms(600));
+        r.update(ms(700));
+        r.release(&"Space", ms(800));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [hold]);
+        assert!(!r.is_pending());
+    }
+
+    #[test]
+    fn press_and_release() {
+        let mut r = PressRecognizer::default();
+        let press = r.add(Chord::new(Modifiers::CONTROL, "A"));
+        let release = r.add(Chord::new(Modifiers::CONTROL, "A").with_press(Press::Release));
+        r.press_modifier(Modifiers::NONE, Modifiers::CONTROL, ms(0));
+        r.press(Modifiers::CONTROL, "A", ms(10));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [press]);
+        r.release(&"A", ms(20));
+        assert_eq!(r.drain().collect::<Vec<_>>(), [release]);
+    }
+}
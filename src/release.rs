@@ -0,0 +1,179 @@
+//! Match chords and sequences when keys are released
+//!
+//! Push-to-talk and "peek" features act when a chord is released rather than
+//! pressed. By the time a key is released the user may have let go of its
+//! modifiers, so a [ReleaseTracker] remembers the modifiers that were held when
+//! each key went down and reports them with the release.
+//!
+//! ```
+//! use keyseq::{Modifiers, release::ReleaseTracker, poor::{pkey, pkeyseq}};
+//! let mut tracker = ReleaseTracker::default();
+//! tracker.press(Modifiers::CONTROL, "Space");
+//! // Ctrl is let go before Space.
+//! tracker.release(&"Space");
+//! for (mods, key) in tracker.drain() {
+//!     match (mods.0, key) {
+//!         pkey! { Ctrl-Space } => println!("Stop talking"),
+//!         _ => {}
+//!     }
+//! }
+//!
+//! tracker.press(Modifiers::NONE, "G");
+//! tracker.release(&"G");
+//! tracker.press(Modifiers::NONE, "G");
+//! tracker.release(&"G");
+//! let seq = pkeyseq! { G G }.map(|(mods, key)| (Modifiers(mods), key));
+//! assert!(tracker.ends_with(&seq));
+//! ```
+use super::{chord::matches_seq, Chord, Modifiers};
+use std::collections::VecDeque;
+
+/// Reports key releases with the modifiers held when the key was pressed.
+///
+/// Feed it presses and releases, then [drain](ReleaseTracker::drain) the
+/// released chords or match the most recent releases against a sequence with
+/// [ends_with](ReleaseTracker::ends_with).
+#[derive(Clone, Debug)]
+pub struct ReleaseTracker<K> {
+    /// Keys that are down with the modifiers held when they were pressed.
+    down: Vec<(Modifiers, K)>,
+    /// The most recent releases, oldest first.
+    history: VecDeque<(Modifiers, K)>,
+    history_len: usize,
+    events: VecDeque<(Modifiers, K)>,
+}
+
+impl<K> Default for ReleaseTracker<K> {
+    fn default() -> Self {
+        ReleaseTracker::new(8)
+    }
+}
+
+impl<K> ReleaseTracker<K> {
+    /// Create a tracker that remembers the last `history_len` releases for
+    /// sequence matching.
+    pub fn new(history_len: usize) -> Self {
+        ReleaseTracker {
+            down: vec![],
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Return the most recent releases, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &(Modifiers, K)> {
+        self.history.iter()
+    }
+
+    /// Forget the release history, e.g., after a sequence matched.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Drain the chords that were released.
+    pub fn drain(&mut self) -> impl Iterator<Item = (Modifiers, K)> + '_ {
+        self.events.drain(..)
+    }
+}
+
+impl<K: Clone + PartialEq> ReleaseTracker<K> {
+    /// Record a non-modifier key press with the modifiers held.
+    pub fn press(&mut self, mods: Modifiers, key: K) {
+        if !self.down.iter().any(|(_, k)| *k == key) {
+            self.down.push((mods, key));
+        }
+    }
+
+    /// Record a key release. Return the chord as it was pressed or `None` if
+    /// the key was not pressed.
+    pub fn release(&mut self, key: &K) -> Option<(Modifiers, K)> {
+        let index = self.down.iter().position(|(_, k)| k == key)?;
+        let chord = self.down.remove(index);
+        if self.history_len > 0 {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+            self.history.push_back(chord.clone());
+        }
+        self.events.push_back(chord.clone());
+        Some(chord)
+    }
+
+    /// Return the modifiers that were held when `key` was pressed if it is
+    /// still down.
+    pub fn pressed_with(&self, key: &K) -> Option<Modifiers> {
+        self.down
+            .iter()
+            .find_map(|(mods, k)| (k == key).then_some(*mods))
+    }
+
+    /// Return true if the most recent releases match `seq`, e.g.,
+    /// `pkeyseq! { Ctrl-X Ctrl-S }`.
+    pub fn ends_with<Q>(&self, seq: &[(Modifiers, Q)]) -> bool
+    where
+        K: PartialEq<Q>,
+    {
+        seq.len() <= self.history.len()
+            && self
+                .history
+                .iter()
+                .skip(self.history.len() - seq.len())
+                .zip(seq)
+                .all(|((mods, key), (m, q))| mods == m && key == q)
+    }
+
+    /// Return true if the most recent releases match the chord sequence
+    /// `chords`, e.g., `pchordseq! { Ctrl-X Ctrl-Shift?-S }`.
+    pub fn ends_with_chords(&self, chords: &[Chord<K>]) -> bool {
+        if chords.len() > self.history.len() {
+            return false;
+        }
+        let recent: Vec<(Modifiers, K)> = self
+            .history
+            .iter()
+            .skip(self.history.len() - chords.len())
+            .cloned()
+            .collect();
+        matches_seq(chords, &recent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_uses_press_modifiers() {
+        let mut tracker = ReleaseTracker::default();
+        tracker.press(Modifiers::CONTROL, "A");
+        assert_eq!(tracker.pressed_with(&"A"), Some(Modifiers::CONTROL));
+        assert_eq!(tracker.release(&"A"), Some((Modifiers::CONTROL, "A")));
+        assert_eq!(tracker.release(&"A"), None);
+        assert_eq!(
+            tracker.drain().collect::<Vec<_>>(),
+            [(Modifiers::CONTROL, "A")]
+        );
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut tracker = ReleaseTracker::new(2);
+        for key in ["X", "Y", "Z"] {
+            tracker.press(Modifiers::CONTROL, key);
+            tracker.release(&key);
+        }
+        assert_eq!(
+            tracker.history().map(|(_, k)| *k).collect::<Vec<_>>(),
+            ["Y", "Z"]
+        );
+        assert!(tracker.ends_with(&[(Modifiers::CONTROL, "Z")]));
+        assert!(!tracker.ends_with(&[(Modifiers::CONTROL, "X"), (Modifiers::CONTROL, "Y")]));
+        assert!(tracker.ends_with_chords(&[
+            Chord::new(Modifiers::CONTROL, "Y"),
+            Chord::new(Modifiers::NONE, "Z").with_optional(Modifiers::CONTROL)
+        ]));
+        tracker.clear_history();
+        assert!(!tracker.ends_with(&[(Modifiers::CONTROL, "Z")]));
+    }
+}
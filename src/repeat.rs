@@ -0,0 +1,265 @@
+//! Auto-repeat chords with a per-binding policy
+//!
+//! Holding `Ctrl-Right` usually moves the cursor again and again. Whether a
+//! binding repeats, and how fast, is a [RepeatPolicy]. A [RepeatMatcher] applies
+//! it the same way whether the key events come from winit, which reports OS
+//! repeats, or bevy's `ButtonInput`, which does not. A custom repeat fires at
+//! most once per update, so a long frame does not cause a burst.
+//!
+//! ```
+//! use std::time::Duration;
+//! use keyseq::{Chord, Modifiers, repeat::{RepeatMatcher, RepeatPolicy}};
+//!
+//! let ms = Duration::from_millis;
+//! let mut matcher = RepeatMatcher::default();
+//! let right = matcher.add(Chord::new(Modifiers::CONTROL, "Right"),
+//!                         RepeatPolicy::Custom { delay: ms(300), interval: ms(50) });
+//! let save = matcher.add(Chord::new(Modifiers::CONTROL, "S"), RepeatPolicy::Ignore);
+//!
+//! matcher.press(Modifiers::CONTROL, "Right", ms(0));
+//! matcher.update(ms(299));
+//! assert_eq!(matcher.drain().collect::<Vec<_>>(), [right]);
+//! matcher.update(ms(300));
+//! matcher.update(ms(350));
+//! assert_eq!(matcher.drain().collect::<Vec<_>>(), [right, right]);
+//! // After a stall, repeat once and keep the interval from then on.
+//! matcher.update(ms(900));
+//! matcher.update(ms(940));
+//! assert_eq!(matcher.drain().collect::<Vec<_>>(), [right]);
+//! matcher.release(&"Right", ms(945));
+//!
+//! matcher.press(Modifiers::CONTROL, "S", ms(1000));
+//! matcher.os_repeat(&"S", ms(1500));
+//! matcher.update(ms(2000));
+//! assert_eq!(matcher.drain().collect::<Vec<_>>(), [save]);
+//! ```
+use super::{Chord, Modifiers};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How a binding responds to a key that is held down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RepeatPolicy {
+    /// Fire only when the key is first pressed.
+    #[default]
+    Ignore,
+    /// Fire again on each repeat reported by the operating system.
+    Os,
+    /// Fire again after `delay` and then every `interval` while held.
+    Custom {
+        /// The time from the press to the first repeat.
+        delay: Duration,
+        /// The time between repeats.
+        interval: Duration,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Held<K> {
+    mods: Modifiers,
+    key: K,
+    /// The next repeat time of each matching `Custom` binding.
+    next: Vec<(usize, Duration)>,
+}
+
+/// Matches chords on press and fires them again while held according to each
+/// binding's [RepeatPolicy].
+#[derive(Clone, Debug)]
+pub struct RepeatMatcher<K> {
+    bindings: Vec<(Chord<K>, RepeatPolicy)>,
+    held: Vec<Held<K>>,
+    events: VecDeque<usize>,
+}
+
+impl<K> Default for RepeatMatcher<K> {
+    fn default() -> Self {
+        RepeatMatcher {
+            bindings: vec![],
+            held: vec![],
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> RepeatMatcher<K> {
+    /// Add a chord with its repeat policy and return its index.
+    pub fn add(&mut self, chord: impl Into<Chord<K>>, policy: RepeatPolicy) -> usize {
+        self.bindings.push((chord.into(), policy));
+        self.bindings.len() - 1
+    }
+
+    /// Add a chord with its repeat policy and return self.
+    pub fn with(mut self, chord: impl Into<Chord<K>>, policy: RepeatPolicy) -> Self {
+        self.add(chord, policy);
+        self
+    }
+
+    /// Return the chords and their policies.
+    pub fn bindings(&self) -> &[(Chord<K>, RepeatPolicy)] {
+        &self.bindings
+    }
+
+    /// Record a key press, not a repeat, with the modifiers held at time
+    /// `now`.
+    pub fn press(&mut self, mods: Modifiers, key: K, now: Duration) {
+        self.update(now);
+        if self.held.iter().any(|held| held.key == key) {
+            // Treat a second press without a release as a repeat.
+            self.os_repeat(&key, now);
+            return;
+        }
+        let mut next = vec![];
+        for (index, (chord, policy)) in self.bindings.iter().enumerate() {
+            if chord.matches(mods, &key) {
+                self.events.push_back(index);
+                if let RepeatPolicy::Custom { delay, .. } = policy {
+                    next.push((index, now + *delay));
+                }
+            }
+        }
+        self.held.push(Held { mods, key, next });
+    }
+
+    /// Record a repeat reported by the operating system at time `now`.
+    pub fn os_repeat(&mut self, key: &K, now: Duration) {
+        self.update(now);
+        let Some(held) = self.held.iter().find(|held| held.key == *key) else {
+            return;
+        };
+        for (index, (chord, policy)) in self.bindings.iter().enumerate() {
+            if *policy == RepeatPolicy::Os && chord.matches(held.mods, &held.key) {
+                self.events.push_back(index);
+            }
+        }
+    }
+
+    /// Record a key release at time `now`.
+    pub fn release(&mut self, key: &K, now: Duration) {
+        self.update(now);
+        self.held.retain(|held| held.key != *key);
+    }
+
+    /// Fire the custom repeats that are due by time `now`, each at most once.
+    /// A repeat more than an interval late is rescheduled from `now`.
+    pub fn update(&mut self, now: Duration) {
+        for held in &mut self.held {
+            for (index, next) in &mut held.next {
+                let RepeatPolicy::Custom { interval, .. } = self.bindings[*index].1 else {
+                    continue;
+                };
+                if *next <= now {
+                    self.events.push_back(*index);
+                    *next += interval;
+                    if *next <= now {
+                        // Fire once per update rather than catching up, and
+                        // at most once per update for a zero interval.
+                        *next = now + interval.max(Duration::from_nanos(1));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return true if a key is held.
+    pub fn is_pending(&self) -> bool {
+        !self.held.is_empty()
+    }
+
+    /// Drain the indices of the chords that fired.
+    pub fn drain(&mut self) -> impl Iterator<Item = usize> + '_ {
+        self.events.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn os_repeat_only_for_os_policy() {
+        let mut matcher = RepeatMatcher::default()
+            .with((Modifiers::NONE, "Left"), RepeatPolicy::Os)
+            .with((Modifiers::NONE, "Left"), RepeatPolicy::Ignore);
+        matcher.press(Modifiers::NONE, "Left", ms(0));
+        assert_eq!(matcher.drain().collect::<Vec<_>>(), [0, 1]);
+        matcher.os_repeat(&"Left", ms(500));
+        matcher.press(Modifiers::NONE, "Left", ms(530));
+        assert_eq!(matcher.drain().collect::<Vec<_>>(), [0, 0]);
+        matcher.release(&"Left", ms(600));
+        matcher.os_repeat(&"Left", ms(630));
+        assert_eq!(matcher.drain().count(), 0);
+    }
+
+    #[test]
+    fn custom_repeat_stops_on_release() {
+        let mut matcher = RepeatMatcher::default().with(
+            (Modifiers::CONTROL, "Right"),
+            RepeatPolicy::Custom {
+                delay: ms(100),
+                interval: ms(10),
+            },
+        );
+        matcher.press(Modifiers::CONTROL, "Right", ms(0));
+        matcher.update(ms(105));
+        assert_eq!(matcher.drain().count(), 2);
+        matcher.release(&"Right", ms(115));
+        assert_eq!(matcher.drain().count(), 1);
+        matcher.update(ms(200));
+        assert_eq!(matcher.drain().count(), 0);
+        assert!(!matcher.is_pending());
+    }
+
+    #[test]
+    fn custom_repeat_does_not_catch_up() {
+        let mut matcher = RepeatMatcher::default().with(
+            (Modifiers::NONE, "Down"),
+            RepeatPolicy::Custom {
+                delay: ms(100),
+                interval: ms(10),
+            },
+        );
+        matcher.press(Modifiers::NONE, "Down", ms(0));
+        matcher.update(ms(1000));
+        assert_eq!(matcher.drain().count(), 2);
+        matcher.update(ms(1005));
+        assert_eq!(matcher.drain().count(), 0);
+        matcher.update(ms(1010));
+        assert_eq!(matcher.drain().count(), 1);
+        matcher.update(ms(1020));
+        assert_eq!(matcher.drain().count(), 1);
+    }
+
+    #[test]
+    fn zero_interval_fires_once_per_update() {
+        let mut matcher = RepeatMatcher::default().with(
+            (Modifiers::NONE, "Down"),
+            RepeatPolicy::Custom {
+                delay: ms(100),
+                interval: Duration::ZERO,
+            },
+        );
+        matcher.press(Modifiers::NONE, "Down", ms(0));
+        matcher.update(ms(100));
+        matcher.update(ms(100));
+        matcher.update(ms(116));
+        assert_eq!(matcher.drain().count(), 3);
+    }
+
+    #[test]
+    fn modifiers_held_at_press() {
+        let mut matcher = RepeatMatcher::default().with(
+            (Modifiers::CONTROL, "Right"),
+            RepeatPolicy::Custom {
+                delay: ms(100),
+                interval: ms(100),
+            },
+        );
+        matcher.press(Modifiers::NONE, "Right", ms(0));
+        matcher.update(ms(500));
+        assert_eq!(matcher.drain().count(), 0);
+    }
+}
@@ -1,6 +1,21 @@
 //! keyseq macros for winit library
-use crate::Modifiers;
-use ::winit::keyboard::ModifiersState;
+use crate::{
+    capture::ChordCapture,
+    combo::ComboDetector,
+    keymap::Leader,
+    layout::{self, Layout},
+    modal::ModalKeymap,
+    modifier_chord::ModifierChordRecognizer,
+    press::PressRecognizer,
+    release::ReleaseTracker,
+    repeat::RepeatMatcher,
+    Modifiers,
+};
+use ::winit::{
+    event::{ElementState, KeyEvent},
+    keyboard::{Key, KeyCode, ModifiersState, NamedKey, PhysicalKey},
+};
+use std::time::Duration;
 
 /// Short hand notation describes a logical key chord as `(modifiers:`
 /// [Modifiers]`, key: `[winit::keyboard::Key][key]`)`.
@@ -44,6 +59,32 @@ pub use keyseq_macros::winit_lkeyseq as lkeyseq;
 /// use winit::keyboard::KeyCode;
 /// assert_eq!(pkey! { A }, (Modifiers::NONE, KeyCode::KeyA));
 /// ```
+///
+/// A chord of only modifiers has no key, so it is a compile error. Use
+/// [pchord!] or [pchordseq!] instead, and see [ModifierChordRecognizer] to
+/// recognize them.
+///
+/// ```compile_fail
+/// use keyseq::{_keyseq, winit::pkey};
+/// let _ = pkey! { Ctrl-Shift };
+/// ```
+///
+/// An optional modifier like `Shift?` expands to a match-or of each
+/// combination, so it only works in a pattern. In an expression it is a
+/// compile error; use [pchord!] to keep it at runtime.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, winit::pkey};
+/// use winit::keyboard::KeyCode;
+/// let zoom_in = |chord| matches!(chord, pkey! { Ctrl-Shift?-Equal });
+/// assert!(zoom_in((Modifiers::CONTROL, KeyCode::Equal)));
+/// assert!(zoom_in((Modifiers::CONTROL | Modifiers::SHIFT, KeyCode::Equal)));
+/// ```
+///
+/// ```compile_fail
+/// use keyseq::{_keyseq, winit::pkey};
+/// let _ = pkey! { Ctrl-Shift?-Equal };
+/// ```
 pub use keyseq_macros::winit_pkey as pkey;
 /// Short hand notation describes a sequence of physical key chords as `[(modifiers:`
 /// [Modifiers]`, key_code: `[winit::keyboard::KeyCode][keycode]`)]`.
@@ -51,6 +92,58 @@ pub use keyseq_macros::winit_pkey as pkey;
 /// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
 pub use keyseq_macros::winit_pkeyseq as pkeyseq;
 
+/// Short hand notation describes a physical key chord as
+/// [Chord](crate::Chord)`<`[winit::keyboard::KeyCode][keycode]`>`.
+///
+/// Unlike [pkey], optional modifiers like `Shift?` are kept at runtime and
+/// treated as "don't care" flags by [Chord::matches](crate::Chord::matches).
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, winit::pchord};
+/// use winit::keyboard::KeyCode;
+/// let zoom_in = pchord! { Ctrl-Shift?-Equal };
+/// assert!(zoom_in.matches(Modifiers::CONTROL, &KeyCode::Equal));
+/// assert!(zoom_in.matches(Modifiers::CONTROL | Modifiers::SHIFT, &KeyCode::Equal));
+/// ```
+///
+/// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
+pub use keyseq_macros::winit_pchord as pchord;
+
+/// Short hand notation describes a sequence of physical key chords as
+/// `[`[Chord](crate::Chord)`<`[winit::keyboard::KeyCode][keycode]`>]`.
+/// Unlike [pkeyseq!], an element may consist only of modifiers.
+///
+/// ```
+/// use keyseq::{Chord, Modifiers, _keyseq, winit::pchordseq};
+/// use winit::keyboard::KeyCode;
+/// assert_eq!(pchordseq! { Ctrl-Shift A },
+///            [Chord::from_modifiers(Modifiers::CONTROL | Modifiers::SHIFT),
+///             Chord::new(Modifiers::NONE, KeyCode::KeyA)]);
+/// ```
+///
+/// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
+pub use keyseq_macros::winit_pchordseq as pchordseq;
+
+/// Short hand notation describes a logical key chord as
+/// [Chord](crate::Chord)`<`[winit::keyboard::Key][key]`>`.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, winit::lchord};
+/// use winit::keyboard::Key;
+/// let zoom_in = lchord! { Ctrl-Shift?-= };
+/// assert!(zoom_in.matches(Modifiers::CONTROL, &Key::Character('=')));
+/// assert!(zoom_in.matches(Modifiers::CONTROL | Modifiers::SHIFT, &Key::Character('=')));
+/// ```
+///
+/// [key]: https://docs.rs/winit/latest/winit/keyboard/enum.Key.html
+pub use keyseq_macros::winit_lchord as lchord;
+
+/// Short hand notation describes a sequence of logical key chords as
+/// `[`[Chord](crate::Chord)`<`[winit::keyboard::Key][key]`>]`.
+///
+/// [key]: https://docs.rs/winit/latest/winit/keyboard/enum.Key.html
+pub use keyseq_macros::winit_lchordseq as lchordseq;
+
 impl From<ModifiersState> for Modifiers {
     fn from(mods: ModifiersState) -> Self {
         let mut r = Modifiers::NONE;
@@ -70,6 +163,19 @@ impl From<ModifiersState> for Modifiers {
     }
 }
 
+impl From<KeyCode> for Modifiers {
+    #[inline(always)]
+    fn from(key: KeyCode) -> Self {
+        match key {
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => Modifiers::SHIFT,
+            KeyCode::ControlLeft | KeyCode::ControlRight => Modifiers::CONTROL,
+            KeyCode::AltLeft | KeyCode::AltRight => Modifiers::ALT,
+            KeyCode::SuperLeft | KeyCode::SuperRight => Modifiers::SUPER,
+            _ => Modifiers::empty(),
+        }
+    }
+}
+
 impl From<Modifiers> for ModifiersState {
     fn from(mods: Modifiers) -> Self {
         let mut r = ModifiersState::empty();
@@ -88,3 +194,350 @@ impl From<Modifiers> for ModifiersState {
         r
     }
 }
+
+/// Short hand notation describes a physical key combo, several keys pressed at
+/// once, as `(modifiers: `[Modifiers]`, key_codes: [`[winit::keyboard::KeyCode][keycode]`; N])`.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, winit::pcombo};
+/// use winit::keyboard::KeyCode;
+/// assert_eq!(pcombo! { J+K }, (Modifiers::NONE, [KeyCode::KeyJ, KeyCode::KeyK]));
+/// ```
+///
+/// [keycode]: https://docs.rs/winit/latest/winit/keyboard/enum.KeyCode.html
+pub use keyseq_macros::winit_pcombo as pcombo;
+
+impl ComboDetector<KeyCode> {
+    /// Read a keyboard event at time `now` with the current modifiers.
+    /// Repeats and modifier keys are not part of combos.
+    ///
+    /// ```
+    /// use std::time::Instant;
+    /// use keyseq::{_keyseq, winit::pcombo, combo::{ComboDetector, ComboEvent}};
+    /// use winit::{event::KeyEvent, keyboard::ModifiersState};
+    ///
+    /// fn on_key(detector: &mut ComboDetector<winit::keyboard::KeyCode>,
+    ///           start: Instant,
+    ///           modifiers: ModifiersState,
+    ///           event: &KeyEvent) {
+    ///     detector.key_event(modifiers.into(), event, start.elapsed());
+    ///     for event in detector.drain() {
+    ///         match event {
+    ///             ComboEvent::Combo(0) => println!("J+K pressed"),
+    ///             ComboEvent::Key(mods, key) => println!("{mods} {key:?} pressed"),
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent, now: Duration) {
+        let PhysicalKey::Code(key) = event.physical_key else {
+            return;
+        };
+        match event.state {
+            ElementState::Pressed if !event.repeat && Modifiers::from(key).is_empty() => {
+                self.press(mods, key, now)
+            }
+            ElementState::Released => self.release(&key, now),
+            _ => self.update(now),
+        }
+    }
+}
+
+impl PressRecognizer<KeyCode> {
+    /// Read a keyboard event at time `now` with the current modifiers. Repeats
+    /// are ignored.
+    ///
+    /// ```
+    /// use std::time::Instant;
+    /// use keyseq::{_keyseq, winit::pchord, press::PressRecognizer};
+    /// use winit::{event::KeyEvent, keyboard::{KeyCode, ModifiersState}};
+    ///
+    /// fn on_key(recognizer: &mut PressRecognizer<KeyCode>,
+    ///           start: Instant,
+    ///           modifiers: ModifiersState,
+    ///           event: &KeyEvent) {
+    ///     recognizer.key_event(modifiers.into(), event, start.elapsed());
+    ///     let fired: Vec<usize> = recognizer.drain().collect();
+    ///     for index in fired {
+    ///         println!("{:?} fired", recognizer.bindings()[index]);
+    ///     }
+    /// }
+    /// let recognizer = PressRecognizer::default().with(pchord! { Shift Shift });
+    /// ```
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent, now: Duration) {
+        let PhysicalKey::Code(key) = event.physical_key else {
+            return;
+        };
+        let modifier = Modifiers::from(key);
+        match event.state {
+            ElementState::Pressed if event.repeat => self.update(now),
+            ElementState::Pressed if modifier.is_empty() => self.press(mods, key, now),
+            ElementState::Pressed => self.press_modifier(mods.difference(modifier), modifier, now),
+            ElementState::Released if modifier.is_empty() => self.release(&key, now),
+            ElementState::Released => self.release_modifier(modifier, now),
+        }
+    }
+}
+
+impl ReleaseTracker<KeyCode> {
+    /// Read a keyboard event with the current modifiers. Repeats and modifier
+    /// keys are ignored.
+    ///
+    /// ```
+    /// use keyseq::{Modifiers, _keyseq, winit::{pkey, pkeyseq}, release::ReleaseTracker};
+    /// use winit::{event::KeyEvent, keyboard::{KeyCode, ModifiersState}};
+    ///
+    /// fn on_key(tracker: &mut ReleaseTracker<KeyCode>,
+    ///           modifiers: ModifiersState,
+    ///           event: &KeyEvent) {
+    ///     tracker.key_event(modifiers.into(), event);
+    ///     for chord in tracker.drain() {
+    ///         if let pkey! { Ctrl-Space } = chord {
+    ///             println!("Stop talking");
+    ///         }
+    ///     }
+    ///     if tracker.ends_with(&pkeyseq! { G G }) {
+    ///         println!("Released G twice");
+    ///     }
+    /// }
+    /// ```
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent) {
+        let PhysicalKey::Code(key) = event.physical_key else {
+            return;
+        };
+        if event.repeat || !Modifiers::from(key).is_empty() {
+            return;
+        }
+        match event.state {
+            ElementState::Pressed => self.press(mods, key),
+            ElementState::Released => {
+                self.release(&key);
+            }
+        }
+    }
+}
+
+impl RepeatMatcher<KeyCode> {
+    /// Read a keyboard event at time `now` with the current modifiers. OS
+    /// repeats fire only [RepeatPolicy::Os](crate::repeat::RepeatPolicy::Os)
+    /// bindings, so there is no need to filter `event.repeat` by hand.
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use keyseq::{_keyseq, winit::pchord, repeat::{RepeatMatcher, RepeatPolicy}};
+    /// use winit::{event::KeyEvent, keyboard::{KeyCode, ModifiersState}};
+    ///
+    /// fn on_key(matcher: &mut RepeatMatcher<KeyCode>,
+    ///           start: Instant,
+    ///           modifiers: ModifiersState,
+    ///           event: &KeyEvent) {
+    ///     matcher.key_event(modifiers.into(), event, start.elapsed());
+    ///     for index in matcher.drain() {
+    ///         println!("binding {index} fired");
+    ///     }
+    /// }
+    /// let ms = Duration::from_millis;
+    /// let matcher = RepeatMatcher::default()
+    ///     .with(pchord! { Ctrl-ArrowRight }, RepeatPolicy::Os)
+    ///     .with(pchord! { Ctrl-S }, RepeatPolicy::Ignore);
+    /// ```
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent, now: Duration) {
+        let PhysicalKey::Code(key) = event.physical_key else {
+            return;
+        };
+        if !Modifiers::from(key).is_empty() {
+            return;
+        }
+        match event.state {
+            ElementState::Pressed if event.repeat => self.os_repeat(&key, now),
+            ElementState::Pressed => self.press(mods, key, now),
+            ElementState::Released => self.release(&key, now),
+        }
+    }
+}
+
+impl<M: Clone + PartialEq, A: Clone> ModalKeymap<M, KeyCode, A> {
+    /// Read a keyboard event with the current modifiers. Only presses of
+    /// non-modifier keys are used, including repeats.
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent) {
+        let PhysicalKey::Code(key) = event.physical_key else {
+            return;
+        };
+        if event.state == ElementState::Pressed && Modifiers::from(key).is_empty() {
+            self.press(mods, key);
+        }
+    }
+}
+
+impl ChordCapture<KeyCode> {
+    /// Read a keyboard event at time `now` with the modifiers `mods` that were
+    /// held. Repeats are ignored.
+    ///
+    /// ```
+    /// use keyseq::{Modifiers, capture::{Capture, ChordCapture}};
+    /// use std::time::{Duration, Instant};
+    /// use winit::{event::KeyEvent, keyboard::KeyCode};
+    ///
+    /// fn on_key(capture: &mut ChordCapture<KeyCode>, mods: Modifiers, event: &KeyEvent, start: Instant) {
+    ///     capture.key_event(mods, event, start.elapsed());
+    ///     match capture.take() {
+    ///         Some(Capture::Chords(chords)) => println!("Rebound to {} chords", chords.len()),
+    ///         Some(Capture::Cancelled) => println!("Cancelled"),
+    ///         None => {}
+    ///     }
+    /// }
+    /// let capture = ChordCapture::new(KeyCode::Escape).sequence(2, Duration::from_secs(1));
+    /// ```
+    pub fn key_event(&mut self, mods: Modifiers, event: &KeyEvent, now: Duration) {
+        if event.repeat {
+            return;
+        }
+        if let PhysicalKey::Code(key) = event.physical_key {
+            let modifier = Modifiers::from(key);
+            match event.state {
+                ElementState::Pressed if modifier.is_empty() => self.press(mods, key, now),
+                ElementState::Pressed => self.press_modifier(modifier),
+                ElementState::Released if !modifier.is_empty() => {
+                    self.release_modifier(modifier, now)
+                }
+                ElementState::Released => {}
+            }
+        }
+        self.update(now);
+    }
+}
+
+impl ModifierChordRecognizer {
+    /// Read a keyboard event. Repeats are ignored.
+    ///
+    /// ```
+    /// use keyseq::{Modifiers, _keyseq, winit::pchord, modifier_chord::ModifierChordRecognizer};
+    /// use winit::event::KeyEvent;
+    ///
+    /// fn on_key(recognizer: &mut ModifierChordRecognizer, event: &KeyEvent) {
+    ///     recognizer.key_event(event);
+    ///     let switch_layout = pchord! { Ctrl-Shift };
+    ///     for mods in recognizer.drain() {
+    ///         match mods {
+    ///             Modifiers::ALT => println!("Open the menu"),
+    ///             mods if switch_layout.matches_modifiers(mods) => println!("Switch layout"),
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn key_event(&mut self, event: &KeyEvent) {
+        if event.repeat {
+            return;
+        }
+        let modifier = match event.physical_key {
+            PhysicalKey::Code(key) => Modifiers::from(key),
+            PhysicalKey::Unidentified(_) => Modifiers::NONE,
+        };
+        match event.state {
+            ElementState::Pressed if modifier.is_empty() => self.press_key(),
+            ElementState::Pressed => self.press_modifier(modifier),
+            ElementState::Released => self.release_modifier(modifier),
+        }
+    }
+}
+
+/// Return the numeric index of a `Digit*`, `F*`, or `Numpad*` key.
+///
+/// This pairs with key ranges like `pkey! { Alt-(1..=9) }` to find which key
+/// matched.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, winit::{key_index, pkey}};
+/// use winit::keyboard::KeyCode;
+/// let select_tab = |chord: (Modifiers, KeyCode)| match chord {
+///     pkey! { Alt-(1..=9) } => key_index(&chord.1),
+///     _ => None,
+/// };
+/// assert_eq!(select_tab((Modifiers::ALT, KeyCode::Digit3)), Some(3));
+/// assert_eq!(select_tab((Modifiers::NONE, KeyCode::Digit3)), None);
+/// assert!(matches!((Modifiers::NONE, KeyCode::F5), pkey! { F(1..=12) }));
+/// ```
+pub fn key_index(key: &KeyCode) -> Option<u8> {
+    key_index!(KeyCode, key)
+}
+
+/// The physical keys of [layout::CODES] in the same order.
+const LAYOUT_KEYS: [KeyCode; 49] = layout_keys!(KeyCode);
+
+/// Return the W3C code of `key` if a [Layout] covers it.
+pub fn code_name(key: &KeyCode) -> Option<&'static str> {
+    LAYOUT_KEYS
+        .iter()
+        .position(|k| k == key)
+        .map(|index| layout::CODES[index])
+}
+
+/// The leader placeholder is `KeyCode::Meta`, a legacy code that winit never
+/// reports for a key event, since `KeyCode` has no unidentified variant.
+///
+/// ```
+/// use keyseq::{Modifiers, _keyseq, keymap::Keymap, winit::pkeyseq};
+/// use winit::keyboard::KeyCode;
+/// let mut keymap = Keymap::new();
+/// let defaults = keymap.add_layer("defaults");
+/// keymap.bind(defaults, pkeyseq! { Leader F S }, "save");
+/// keymap.set_leader(Modifiers::NONE, KeyCode::Space);
+/// assert_eq!(keymap.resolve(&pkeyseq! { Space F S }), Some(&"save"));
+/// assert_ne!(keymap.resolve(&pkeyseq! { F35 F S }), Some(&"save"));
+/// ```
+impl Leader for KeyCode {
+    fn leader() -> Self {
+        KeyCode::Meta
+    }
+}
+
+/// Return the logical chord typed by the physical chord `mods` and `key` on
+/// `layout`. See [Layout::to_logical].
+///
+/// ```
+/// use keyseq::{_keyseq, layout::Layout, winit::{pkey, lkey, to_logical}};
+/// let logical = |(mods, key)| to_logical(Layout::FrenchAzerty, mods, key);
+/// assert_eq!(logical(pkey! { Ctrl-Semicolon }), Some(lkey! { Ctrl-m }));
+/// assert_eq!(logical(pkey! { Shift-Q }), Some(lkey! { Shift-a }));
+/// assert_eq!(logical(pkey! { Shift-1 }), Some(lkey! { 1 }));
+/// assert_eq!(logical(pkey! { Space }), Some(lkey! { Space }));
+/// ```
+pub fn to_logical(layout: Layout, mods: Modifiers, key: KeyCode) -> Option<(Modifiers, Key<char>)> {
+    let (mods, ch) = layout.to_logical(mods, code_name(&key)?)?;
+    Some((
+        mods,
+        if ch == ' ' {
+            Key::Named(NamedKey::Space)
+        } else {
+            Key::Character(ch)
+        },
+    ))
+}
+
+/// Return the physical chord that types the logical chord `mods` and `key` on
+/// `layout`, adding Shift if needed. See [Layout::to_physical].
+///
+/// ```
+/// use keyseq::{_keyseq, layout::Layout, winit::{pkey, lkey, to_physical}};
+/// let physical = |(mods, key)| to_physical(Layout::GermanQwertz, mods, &key);
+/// assert_eq!(physical(lkey! { Ctrl-z }), Some(pkey! { Ctrl-Y }));
+/// assert_eq!(physical(lkey! { Shift-z }), Some(pkey! { Shift-Y }));
+/// assert_eq!(physical(lkey! { Z }), Some(pkey! { Shift-Y }));
+/// assert_eq!(physical(lkey! { Shift-Space }), Some(pkey! { Shift-Space }));
+/// ```
+pub fn to_physical(
+    layout: Layout,
+    mods: Modifiers,
+    key: &Key<char>,
+) -> Option<(Modifiers, KeyCode)> {
+    let ch = match key {
+        Key::Named(NamedKey::Space) => ' ',
+        Key::Character(ch) => *ch,
+        _ => return None,
+    };
+    let (mods, code) = layout.to_physical(mods, ch)?;
+    let index = layout::CODES.iter().position(|c| *c == code)?;
+    Some((mods, LAYOUT_KEYS[index]))
+}
@@ -1,7 +1,7 @@
 #[cfg(feature = "bevy")]
 mod for_bevy {
     use ::bevy::input::keyboard::{Key, KeyCode};
-    use keyseq::{bevy::*, Modifiers};
+    use keyseq::{_keyseq, bevy::*, Modifiers};
 
     #[test]
     fn check_modifiers() {
@@ -19,6 +19,448 @@ mod for_bevy {
         }
     }
 
+    #[test]
+    fn check_optional_match() {
+        let zoom_in = |chord| matches!(chord, pkey! { Ctrl-Shift?-Equal });
+        assert!(zoom_in((Modifiers::CONTROL, KeyCode::Equal)));
+        assert!(zoom_in((
+            Modifiers::CONTROL | Modifiers::SHIFT,
+            KeyCode::Equal
+        )));
+        assert!(!zoom_in((Modifiers::SHIFT, KeyCode::Equal)));
+        assert!(!zoom_in((
+            Modifiers::CONTROL | Modifiers::ALT,
+            KeyCode::Equal
+        )));
+    }
+
+    #[test]
+    fn check_optional_chord() {
+        let zoom_in = pchord! { Ctrl-Shift?-Equal };
+        assert!(zoom_in.matches(Modifiers::CONTROL, &KeyCode::Equal));
+        assert!(zoom_in.matches(Modifiers::CONTROL | Modifiers::SHIFT, &KeyCode::Equal));
+        assert!(!zoom_in.matches(Modifiers::SHIFT, &KeyCode::Equal));
+
+        let zoom_in = lchord! { Ctrl-Shift?-= };
+        assert!(zoom_in.matches(Modifiers::CONTROL, &Key::Character("=".into())));
+        assert!(zoom_in.matches(
+            Modifiers::CONTROL | Modifiers::SHIFT,
+            &Key::Character("=".into())
+        ));
+    }
+
+    #[test]
+    fn check_range_match() {
+        let tab = |chord: (Modifiers, KeyCode)| match chord {
+            pkey! { Alt-(1..=9) } => key_index(&chord.1),
+            pkey! { Ctrl-F(1..=4) } => key_index(&chord.1).map(|n| n + 10),
+            _ => None,
+        };
+        assert_eq!(tab((Modifiers::ALT, KeyCode::Digit1)), Some(1));
+        assert_eq!(tab((Modifiers::ALT, KeyCode::Digit9)), Some(9));
+        assert_eq!(tab((Modifiers::ALT, KeyCode::Digit0)), None);
+        assert_eq!(tab((Modifiers::CONTROL, KeyCode::F4)), Some(14));
+        assert_eq!(tab((Modifiers::CONTROL, KeyCode::F5)), None);
+        assert!(matches!(
+            pkeyseq! { Ctrl-X 1 }[1],
+            (Modifiers::NONE, KeyCode::Digit1)
+        ));
+    }
+
+    #[test]
+    fn check_modifier_chord() {
+        use keyseq::{Chord, Press};
+        // Every element of a sequence has the same type.
+        let seq: [Chord<KeyCode>; 3] = pchordseq! { Ctrl-Shift A Alt };
+        assert_eq!(seq[0].key, None);
+        assert_eq!(seq[0].mods, Modifiers::CONTROL | Modifiers::SHIFT);
+        assert_eq!(seq[1], Chord::new(Modifiers::NONE, KeyCode::KeyA));
+        assert_eq!(seq[2], Chord::from_modifiers(Modifiers::ALT));
+        assert_eq!(seq[2].press, Press::Tap);
+        let menu = pchord! { Alt };
+        assert!(menu.matches_modifiers(Modifiers::ALT));
+        assert!(!menu.matches_modifiers(Modifiers::ALT | Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn check_combo_tap_in_one_frame() {
+        use ::bevy::input::ButtonInput;
+        use keyseq::combo::{ComboDetector, ComboEvent};
+        use std::time::Duration;
+
+        let mut detector = ComboDetector::new(Duration::from_millis(50)).with(pcombo! { J+K });
+        let mut input = ButtonInput::<KeyCode>::default();
+        input.press(KeyCode::KeyJ);
+        input.release(KeyCode::KeyJ);
+        detector.read_input(&input, Duration::ZERO);
+        assert!(!detector.is_pending());
+        assert_eq!(
+            detector.drain().collect::<Vec<_>>(),
+            [ComboEvent::Key(Modifiers::NONE, KeyCode::KeyJ)]
+        );
+    }
+
+    #[test]
+    fn check_release_seq() {
+        use ::bevy::input::ButtonInput;
+        use keyseq::release::ReleaseTracker;
+        let mut tracker = ReleaseTracker::default();
+        let mut input = ButtonInput::<KeyCode>::default();
+        for key in [KeyCode::KeyX, KeyCode::KeyS] {
+            input.press(KeyCode::ControlLeft);
+            input.press(key);
+            tracker.read_input(&input);
+            input.clear();
+            input.release(KeyCode::ControlLeft);
+            input.release(key);
+            tracker.read_input(&input);
+            input.clear();
+        }
+        assert!(tracker.ends_with(&pkeyseq! { Ctrl-X Ctrl-S }));
+        assert!(tracker.ends_with_chords(&pchordseq! { Ctrl-X Ctrl-Shift?-S }));
+        assert!(!tracker.ends_with(&pkeyseq! { X S }));
+    }
+
+    #[test]
+    fn check_keymap() {
+        use keyseq::{bindings::KeyBindings, keymap};
+        keymap! {
+            EditorKeys for bevy {
+                Ctrl-X Ctrl-S => Save,
+                Ctrl-Z => Undo,
+                Ctrl-Shift-; => Command,
+            }
+        }
+        assert_eq!(
+            EditorKeys::lookup(&pkeyseq! { Ctrl-X Ctrl-S }),
+            Some(EditorKeys::Save)
+        );
+        assert_eq!(
+            EditorKeys::lookup_chord(Modifiers::CONTROL, &KeyCode::KeyZ),
+            Some(EditorKeys::Undo)
+        );
+        assert_eq!(EditorKeys::lookup(&pkeyseq! { Ctrl-X }), None);
+        assert!(EditorKeys::is_prefix(&pkeyseq! { Ctrl-X }));
+        assert_eq!(EditorKeys::Command.to_string(), "Command (Ctrl-Shift-;)");
+    }
+
+    #[test]
+    fn check_derive_key_bindings() {
+        use keyseq::bindings::KeyBindings;
+        #[derive(Clone, Copy, Debug, PartialEq, KeyBindings)]
+        #[key_bindings(bevy)]
+        enum Action {
+            #[keys(Ctrl-S)]
+            Save,
+            /// Leave the game.
+            #[keys(Ctrl-X Ctrl-C, Alt-F4)]
+            Quit,
+        }
+        assert_eq!(
+            Action::lookup(&pkeyseq! { Ctrl-X Ctrl-C }),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            Action::Save.default_keys().collect::<Vec<_>>(),
+            [&pkeyseq! { Ctrl-S }[..]]
+        );
+        assert_eq!(Action::Save.to_string(), "Save (Ctrl-S)");
+    }
+
+    #[test]
+    fn check_keymap_layers() {
+        use keyseq::{bindings::KeyBindings, keymap::Keymap};
+        #[derive(Clone, Copy, Debug, PartialEq, KeyBindings)]
+        #[key_bindings(bevy)]
+        enum Action {
+            #[keys(Ctrl-S)]
+            Save,
+            #[keys(Ctrl-Q)]
+            Quit,
+        }
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        let user = keymap.add_layer("user");
+        keymap.bind_all::<Action>(defaults);
+        keymap.bind(user, pkeyseq! { Ctrl-W }, Action::Quit);
+        keymap.unbind(user, pkeyseq! { Ctrl-Q });
+        assert_eq!(
+            keymap.resolve_chord(Modifiers::CONTROL, KeyCode::KeyW),
+            Some(&Action::Quit)
+        );
+        assert_eq!(keymap.resolve(&pkeyseq! { Ctrl-Q }), None);
+        assert_eq!(keymap.diff(user).len(), 2);
+    }
+
+    #[test]
+    fn check_leader() {
+        use keyseq::{bindings::KeyBindings, keymap::Keymap};
+        #[derive(Clone, Copy, Debug, PartialEq, KeyBindings)]
+        #[key_bindings(bevy)]
+        enum Action {
+            #[keys(Leader F S)]
+            Save,
+            #[keys(Leader Q)]
+            Quit,
+        }
+        let mut keymap = Keymap::new();
+        let defaults = keymap.add_layer("defaults");
+        keymap.bind_all::<Action>(defaults);
+        keymap.bind(defaults, pkeyseq! { Ctrl-Leader }, Action::Quit);
+        keymap.set_leader(Modifiers::NONE, KeyCode::Space);
+        assert_eq!(keymap.resolve(&pkeyseq! { Space F S }), Some(&Action::Save));
+        assert_eq!(
+            keymap.resolve(&pkeyseq! { Ctrl-Space }),
+            Some(&Action::Quit)
+        );
+        keymap.set_leader(Modifiers::NONE, KeyCode::Comma);
+        assert_eq!(keymap.resolve(&pkeyseq! { Space Q }), None);
+        assert_eq!(keymap.resolve(&pkeyseq! { , Q }), Some(&Action::Quit));
+    }
+
+    #[test]
+    fn check_modal_keymap_state() {
+        use ::bevy::{prelude::*, state::app::StatesPlugin};
+        use keyseq::modal::{ModalEvent, ModalKeymap};
+
+        #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        enum Mode {
+            #[default]
+            Normal,
+            Insert,
+        }
+
+        let mut keymap = ModalKeymap::<_, _, &'static str>::new(Mode::Normal);
+        keymap.switch(Mode::Normal, pkeyseq! { I }, Mode::Insert);
+        keymap.set_passthrough(Mode::Insert, true);
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_state::<Mode>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .insert_resource(keymap)
+            .add_systems(Update, read_modal_keymap::<Mode, &'static str>);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyI);
+        app.update();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+        assert_eq!(*app.world().resource::<State<Mode>>().get(), Mode::Insert);
+
+        // The keymap follows the state too.
+        app.world_mut()
+            .resource_mut::<NextState<Mode>>()
+            .set(Mode::Normal);
+        app.update();
+        let mut keymap = app
+            .world_mut()
+            .resource_mut::<ModalKeymap<Mode, KeyCode, &'static str>>();
+        assert_eq!(*keymap.current(), Mode::Normal);
+        assert_eq!(
+            keymap.drain().collect::<Vec<_>>(),
+            [ModalEvent::ModeChanged {
+                from: Mode::Normal,
+                to: Mode::Insert
+            }]
+        );
+    }
+
+    /// Press only `keys` for one update and return the key binding actions
+    /// fired with their entities.
+    fn press(
+        app: &mut ::bevy::app::App,
+        keys: &[KeyCode],
+    ) -> Vec<(&'static str, Option<::bevy::ecs::entity::Entity>)> {
+        use ::bevy::prelude::*;
+
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        for key in keys {
+            input.press(*key);
+        }
+        app.update();
+        app.world_mut()
+            .resource_mut::<Messages<KeyBindingAction<&'static str>>>()
+            .drain()
+            .map(|message| (message.action, message.entity))
+            .collect()
+    }
+
+    #[test]
+    fn check_focus_key_bindings() {
+        use ::bevy::{input_focus::InputFocus, prelude::*};
+
+        let mut app = App::new();
+        app.add_plugins(KeyBindingsPlugin::<&'static str>::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<InputFocus>()
+            .init_resource::<Time>();
+        app.world_mut()
+            .resource_mut::<GlobalKeyBindings<&'static str>>()
+            .0
+            .bind(pkeyseq! { Ctrl-F }, "global-find");
+        let panel = app
+            .world_mut()
+            .spawn(KeyBindings::new().with(pkeyseq! { Ctrl-F }, "panel-find"))
+            .id();
+        let field = app
+            .world_mut()
+            .spawn((
+                KeyBindings::new().with(pkeyseq! { Ctrl-K Ctrl-C }, "comment"),
+                ChildOf(panel),
+            ))
+            .id();
+
+        // No focus: global bindings only.
+        assert_eq!(
+            press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyF]),
+            [("global-find", None)]
+        );
+        // The focused field does not bind Ctrl-F, so it bubbles to the panel.
+        app.world_mut()
+            .insert_resource(InputFocus::from_entity(field));
+        assert_eq!(
+            press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyF]),
+            [("panel-find", Some(panel))]
+        );
+        assert!(press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyK]).is_empty());
+        assert_eq!(
+            press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyC]),
+            [("comment", Some(field))]
+        );
+    }
+
+    #[test]
+    fn check_text_sink() {
+        use ::bevy::{input_focus::InputFocus, prelude::*};
+
+        let mut app = App::new();
+        app.add_plugins(KeyBindingsPlugin::<&'static str>::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Time>();
+        {
+            let mut global = app
+                .world_mut()
+                .resource_mut::<GlobalKeyBindings<&'static str>>();
+            global.0.bind(pkeyseq! { G G }, "top");
+            global.0.bind(pkeyseq! { Ctrl-G }, "goto");
+        }
+        let field = app.world_mut().spawn(TextSink).id();
+        app.world_mut()
+            .insert_resource(InputFocus::from_entity(field));
+
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        assert_eq!(
+            press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyG]),
+            [("goto", None)]
+        );
+        app.world_mut().resource_mut::<InputFocus>().clear();
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        assert_eq!(press(&mut app, &[KeyCode::KeyG]), [("top", None)]);
+    }
+
+    #[test]
+    fn check_sequence_timeout() {
+        use ::bevy::prelude::*;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins(KeyBindingsPlugin::<&'static str>::default())
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Time>()
+            .insert_resource(KeySequenceTimeout(Some(Duration::from_secs(1))));
+        {
+            let mut global = app
+                .world_mut()
+                .resource_mut::<GlobalKeyBindings<&'static str>>();
+            global.0.bind(pkeyseq! { G G }, "top");
+            global.0.bind(pkeyseq! { G }, "down");
+            global.0.bind(pkeyseq! { D }, "delete");
+            global.0.bind(pkeyseq! { Ctrl-K Ctrl-C }, "comment");
+        }
+
+        let wait = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs(2));
+        };
+
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        assert_eq!(press(&mut app, &[KeyCode::KeyG]), [("top", None)]);
+        // `G` alone fires once the timeout runs out.
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        wait(&mut app);
+        assert_eq!(press(&mut app, &[]), [("down", None)]);
+        // So does `G` when another key breaks the sequence.
+        assert!(press(&mut app, &[KeyCode::KeyG]).is_empty());
+        assert_eq!(
+            press(&mut app, &[KeyCode::KeyD]),
+            [("down", None), ("delete", None)]
+        );
+        // An unfinished prefix is dropped.
+        assert!(press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyK]).is_empty());
+        wait(&mut app);
+        assert!(press(&mut app, &[]).is_empty());
+        assert!(press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyC]).is_empty());
+        assert!(app
+            .world()
+            .resource::<PendingKeys<&'static str>>()
+            .keys()
+            .is_empty());
+    }
+
+    #[cfg(feature = "which-key")]
+    #[test]
+    fn check_which_key() {
+        use ::bevy::{prelude::*, ui::widget::Text};
+        use keyseq::display::Style;
+        use std::time::Duration;
+
+        let mut app = App::new();
+        app.add_plugins((
+            KeyBindingsPlugin::<&'static str>::default(),
+            WhichKeyPlugin::<&'static str>::default(),
+        ))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<Time>();
+        app.world_mut().resource_mut::<WhichKey>().style = Style::Keyseq;
+        {
+            let mut global = app
+                .world_mut()
+                .resource_mut::<GlobalKeyBindings<&'static str>>();
+            global.0.bind(pkeyseq! { Ctrl-X Ctrl-S }, "save");
+            global.0.bind(pkeyseq! { Ctrl-X R M }, "bookmark");
+            global.0.bind(pkeyseq! { Ctrl-X R J }, "jump");
+        }
+
+        let overlay = |app: &mut App| {
+            app.world_mut()
+                .query_filtered::<&Text, With<WhichKeyOverlay>>()
+                .iter(app.world())
+                .map(|text| text.0.clone())
+                .collect::<Vec<_>>()
+        };
+
+        press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyX]);
+        assert_eq!(
+            app.world().resource::<PendingKeys<&'static str>>().keys(),
+            pkeyseq! { Ctrl-X }
+        );
+        assert!(overlay(&mut app).is_empty());
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(2));
+        press(&mut app, &[]);
+        assert_eq!(overlay(&mut app), ["Ctrl-S  save\nR  +2"]);
+        press(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyS]);
+        assert!(overlay(&mut app).is_empty());
+    }
+
     #[test]
     fn check_display() {
         assert_eq!(pkey! { A }.0.to_string(), "");